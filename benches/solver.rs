@@ -0,0 +1,88 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use sym::{bigint::BigInt, polynomial::Polynomial, rational::Rational, solver};
+
+/// Build the polynomial `(x - 1)(x - 2)...(x - n)`, whose coefficients grow quickly with `n`.
+fn with_roots_up_to(n: i32) -> Polynomial {
+    (1..=n).fold(
+        Polynomial::from(vec![(0, Rational::from(1))]),
+        |product, root| {
+            product * Polynomial::from(vec![(0, Rational::from(-root)), (1, Rational::from(1))])
+        },
+    )
+}
+
+fn bigint_multiplication(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bigint_mul");
+
+    for bytes in [16, 64, 256, 1024] {
+        let a = BigInt::from_be_bytes(&vec![0xab; bytes]);
+        let b = BigInt::from_be_bytes(&vec![0xcd; bytes]);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(bytes),
+            &(a, b),
+            |bencher, (a, b)| bencher.iter(|| black_box(a.clone()) * black_box(b.clone())),
+        );
+    }
+
+    group.finish();
+}
+
+fn solving(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve_univariate_polynomial");
+
+    for degree in [4, 6, 8] {
+        let poly = with_roots_up_to(degree);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(degree),
+            &poly,
+            |bencher, poly| bencher.iter(|| solver::solve_univariate_polynomial(black_box(poly))),
+        );
+    }
+
+    group.finish();
+}
+
+fn factoring(c: &mut Criterion) {
+    // The rational root theorem has to enumerate all the divisors of the constant term, so `x^2 - n` is dominated by
+    // factoring `n`
+    let mut group = c.benchmark_group("rational_roots");
+
+    for n in [1_000, 100_000, 10_000_000] {
+        let poly = Polynomial::from(vec![(0, Rational::from(-n)), (2, Rational::from(1))]);
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &poly, |bencher, poly| {
+            bencher.iter(|| solver::rational_roots(black_box(poly)))
+        });
+    }
+
+    group.finish();
+}
+
+fn rational_equality(c: &mut Criterion) {
+    // Rationals are always in lowest terms, so comparing them shouldn't need a GCD, no matter how large they are
+    let mut group = c.benchmark_group("rational_eq");
+
+    for (name, value) in [
+        ("small", Rational::new(3, 4)),
+        ("large", Rational::new(i128::MAX, i128::MAX - 1)),
+    ] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &value,
+            |bencher, value| bencher.iter(|| black_box(value) == black_box(value)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bigint_multiplication,
+    solving,
+    factoring,
+    rational_equality
+);
+criterion_main!(benches);
@@ -0,0 +1,941 @@
+use alloc::{vec, vec::Vec};
+use core::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+};
+
+/// An arbitrary-precision signed integer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    // Magnitude stored as little-endian base 2^32 limbs without any trailing zero limbs, so zero is an empty vector.
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt {
+            negative: false,
+            limbs: Vec::new(),
+        }
+    }
+
+    fn from_limbs(negative: bool, mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+
+        BigInt {
+            // Make sure there is no negative zero
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        BigInt {
+            negative: false,
+            limbs: self.limbs.clone(),
+        }
+    }
+
+    /// Get the number of significant bits in the magnitude, which is 0 for zero.
+    pub fn bit_length(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Check whether the integer is divisible by two. Zero is even.
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|low| low % 2 == 0)
+    }
+
+    /// Convert to an `i128`, returning `None` if the value doesn't fit.
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0u128, |acc, &limb| (acc << 32) | limb as u128);
+
+        if self.negative {
+            // The magnitude of `i128::MIN` is one larger than `i128::MAX`
+            if magnitude <= i128::MIN.unsigned_abs() {
+                Some((magnitude as i128).wrapping_neg())
+            } else {
+                None
+            }
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// Get the magnitude as big-endian bytes without any leading zero bytes, so zero is empty. The sign isn't included
+    /// and has to be stored separately using [`BigInt::is_negative`].
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        self.limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .skip_while(|&byte| byte == 0)
+            .collect()
+    }
+
+    /// Build a non-negative integer from its big-endian bytes. Leading zero bytes are allowed.
+    pub fn from_be_bytes(bytes: &[u8]) -> Self {
+        // Group the bytes into limbs starting from the least significant end
+        let limbs = bytes
+            .rchunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |limb, &byte| (limb << 8) | byte as u32)
+            })
+            .collect();
+
+        BigInt::from_limbs(false, limbs)
+    }
+
+    /// Divide by `divisor`, returning the quotient and the remainder. Like the primitive integer types, the quotient is
+    /// rounded towards zero and the remainder has the same sign as the dividend.
+    pub fn div_rem(&self, divisor: &BigInt) -> (BigInt, BigInt) {
+        if divisor.is_zero() {
+            panic!("attempt to divide by zero");
+        }
+
+        let (quotient, remainder) = div_rem_magnitude(&self.limbs, &divisor.limbs);
+
+        (
+            BigInt::from_limbs(self.negative != divisor.negative, quotient),
+            BigInt::from_limbs(self.negative, remainder),
+        )
+    }
+
+    /// Iterate over the integers from `start` up to, but not including, `end`.
+    pub fn range(start: BigInt, end: BigInt) -> Range {
+        Range { next: start, end }
+    }
+
+    /// Raise to the power of `exponent`, where `0^0` is 1.
+    pub fn pow(&self, mut exponent: u32) -> BigInt {
+        // Binary exponentiation (https://en.wikipedia.org/wiki/Exponentiation_by_squaring)
+        let mut result = BigInt::from(1);
+        let mut base = self.clone();
+
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = &result * &base;
+            }
+
+            exponent /= 2;
+
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    /// Convert to the closest `f64`, which is infinite if the value is too large.
+    pub fn to_f64(&self) -> f64 {
+        let (mantissa, exponent) = self.to_scaled_f64();
+
+        scale_f64(mantissa, exponent)
+    }
+
+    /// Split into a float `m` holding the (rounded) top bits of the value and an exponent `e`, such that the value is
+    /// approximately `m * 2^e`. Unlike the value itself, `m` never overflows.
+    pub(crate) fn to_scaled_f64(&self) -> (f64, i32) {
+        // 3 limbs are more than enough to fill the 53 bits of an f64's mantissa
+        let skipped = self.limbs.len().saturating_sub(3);
+        let top = self.limbs[skipped..]
+            .iter()
+            .rev()
+            .fold(0u128, |acc, &limb| (acc << 32) | limb as u128);
+
+        let mantissa = if self.negative {
+            -(top as f64)
+        } else {
+            top as f64
+        };
+
+        (mantissa, 32 * skipped as i32)
+    }
+
+    /// Get the (non-negative) greatest common divisor of two integers.
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        // Use Euclidean algorithm to find the GCD (https://en.wikipedia.org/wiki/Greatest_common_divisor#Euclidean_algorithm)
+        let mut a = self.abs();
+        let mut b = other.abs();
+
+        while !b.is_zero() {
+            let t = a.div_rem(&b).1;
+            a = b;
+            b = t;
+        }
+
+        a
+    }
+
+    /// Get the floor of the square root of a non-negative integer.
+    pub fn isqrt(&self) -> BigInt {
+        if self.negative {
+            panic!("attempt to take the square root of a negative number");
+        }
+
+        if self.is_zero() {
+            return BigInt::zero();
+        }
+
+        // Start from a power of two that's at least the root, after which Newton's method
+        // (https://en.wikipedia.org/wiki/Integer_square_root#Algorithm_using_Newton's_method) decreases monotonically
+        // until it reaches the floor of the root
+        let shift = self.bit_length().div_ceil(2);
+        let mut limbs = vec![0; shift / 32 + 1];
+        limbs[shift / 32] = 1 << (shift % 32);
+
+        let mut root = BigInt::from_limbs(false, limbs);
+
+        loop {
+            let next = (&root + &self.div_rem(&root).0).div_rem(&BigInt::from(2)).0;
+
+            if next >= root {
+                return root;
+            }
+
+            root = next;
+        }
+    }
+
+    /// Get the floor of the `n`-th root of a non-negative integer.
+    pub fn nth_root(&self, n: u32) -> BigInt {
+        if self.negative {
+            panic!("attempt to take a root of a negative number");
+        }
+
+        if n == 1 || self.is_zero() {
+            return self.clone();
+        }
+
+        // Anything below 2^n has a root below 2, which also keeps huge `n` from building huge powers below
+        if n as usize >= self.bit_length() {
+            return BigInt::from(1);
+        }
+
+        // Like in `isqrt`, Newton's method decreases monotonically from a power of two that's at least the root
+        let shift = self.bit_length().div_ceil(n as usize);
+        let mut limbs = vec![0; shift / 32 + 1];
+        limbs[shift / 32] = 1 << (shift % 32);
+
+        let mut root = BigInt::from_limbs(false, limbs);
+        let n_big = BigInt::from(n as i128);
+
+        loop {
+            // x' = ((n - 1) x + a/x^(n - 1))/n
+            let next = (&(&BigInt::from(n as i128 - 1) * &root)
+                + &self.div_rem(&root.pow(n - 1)).0)
+                .div_rem(&n_big)
+                .0;
+
+            if next >= root {
+                return root;
+            }
+
+            root = next;
+        }
+    }
+
+    /// Check whether the integer is the square of another integer.
+    pub fn is_perfect_square(&self) -> bool {
+        if self.negative {
+            return false;
+        }
+
+        let root = self.isqrt();
+
+        &root * &root == *self
+    }
+}
+
+/// Compute `value * 2^exponent`, going through the power of two in steps so that it never overflows by itself.
+pub(crate) fn scale_f64(mut value: f64, mut exponent: i32) -> f64 {
+    // 2^e for any exponent in the normal range, built directly from its IEEE-754 representation
+    let power_of_two = |exponent: i32| f64::from_bits(((exponent + 1023) as u64) << 52);
+
+    while exponent > 1000 {
+        value *= power_of_two(1000);
+        exponent -= 1000;
+    }
+
+    while exponent < -1000 {
+        value *= power_of_two(-1000);
+        exponent += 1000;
+    }
+
+    value * power_of_two(exponent)
+}
+
+/// A lazy iterator over a half-open range of integers, created by [`BigInt::range`].
+#[derive(Clone, Debug)]
+pub struct Range {
+    next: BigInt,
+    end: BigInt,
+}
+
+impl Iterator for Range {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+
+        let next = &self.next + &BigInt::from(1);
+
+        Some(core::mem::replace(&mut self.next, next))
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+
+    result.push(carry as u32);
+    trim(&mut result);
+
+    result
+}
+
+/// Subtract the magnitude `b` from `a`, which must not be smaller than `b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for (i, &limb) in a.iter().enumerate() {
+        let mut difference = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+
+        if difference < 0 {
+            difference += 1 << 32;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+
+        result.push(difference as u32);
+    }
+
+    trim(&mut result);
+
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    // Use schoolbook long multiplication (https://en.wikipedia.org/wiki/Multiplication_algorithm#Long_multiplication)
+    let mut result = vec![0; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+
+        for (j, &y) in b.iter().enumerate() {
+            // This can't overflow as (2^32 - 1)^2 + 2 * (2^32 - 1) = 2^64 - 1
+            let product = x as u64 * y as u64 + result[i + j] as u64 + carry;
+
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+
+        result[i + b.len()] = carry as u32;
+    }
+
+    trim(&mut result);
+
+    result
+}
+
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_magnitude(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+
+    // Dividing by a single limb can be done a whole limb at a time
+    if let [divisor] = *b {
+        let mut quotient = vec![0; a.len()];
+        let mut remainder = 0;
+
+        for (i, &limb) in a.iter().enumerate().rev() {
+            let current = (remainder << 32) | limb as u64;
+
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+
+        return (quotient, vec![remainder as u32]);
+    }
+
+    // Otherwise fall back to binary long division (https://en.wikipedia.org/wiki/Division_algorithm#Long_division)
+    let mut quotient = vec![0; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+
+    for bit in (0..a.len() * 32).rev() {
+        // Shift the next bit of the dividend into the remainder
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+
+        for limb in remainder.iter_mut() {
+            let next_carry = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next_carry;
+        }
+
+        if carry != 0 {
+            remainder.push(carry);
+        }
+
+        if cmp_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+
+    (quotient, remainder)
+}
+
+impl From<i128> for BigInt {
+    fn from(x: i128) -> Self {
+        let magnitude = x.unsigned_abs();
+
+        BigInt::from_limbs(
+            x < 0,
+            (0..4).map(|i| (magnitude >> (32 * i)) as u32).collect(),
+        )
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(x: i64) -> Self {
+        BigInt::from(x as i128)
+    }
+}
+
+impl From<i32> for BigInt {
+    fn from(x: i32) -> Self {
+        BigInt::from(x as i128)
+    }
+}
+
+/// Add `a` and `b`, where `b` is taken to be negative if `b_negative` is set regardless of its own sign. This lets
+/// subtraction flip the sign without cloning `b`.
+fn add_signed(a: &BigInt, b: &BigInt, b_negative: bool) -> BigInt {
+    if a.negative == b_negative {
+        return BigInt::from_limbs(a.negative, add_magnitude(&a.limbs, &b.limbs));
+    }
+
+    // The signs differ, so subtract the smaller magnitude from the larger one and keep the sign of the larger one
+    match cmp_magnitude(&a.limbs, &b.limbs) {
+        Ordering::Less => BigInt::from_limbs(b_negative, sub_magnitude(&b.limbs, &a.limbs)),
+        _ => BigInt::from_limbs(a.negative, sub_magnitude(&a.limbs, &b.limbs)),
+    }
+}
+
+// The arithmetic is implemented on references so that accumulating loops don't have to clone their operands, with the
+// by-value operators delegating to them
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: Self) -> BigInt {
+        add_signed(self, other, other.negative)
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: Self) -> BigInt {
+        add_signed(self, other, !other.negative)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_limbs(!self.negative, self.limbs.clone())
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: Self) -> BigInt {
+        BigInt::from_limbs(
+            self.negative != other.negative,
+            mul_magnitude(&self.limbs, &other.limbs),
+        )
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: Self) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: Self) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+impl Add for BigInt {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        &self + &other
+    }
+}
+
+impl Sub for BigInt {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        &self - &other
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        // The limbs can be reused as they are
+        BigInt::from_limbs(!self.negative, self.limbs)
+    }
+}
+
+impl Mul for BigInt {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        &self * &other
+    }
+}
+
+impl Div for BigInt {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        &self / &other
+    }
+}
+
+impl Rem for BigInt {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        &self % &other
+    }
+}
+
+impl AddAssign<&BigInt> for BigInt {
+    fn add_assign(&mut self, other: &BigInt) {
+        *self = &*self + other;
+    }
+}
+
+impl SubAssign<&BigInt> for BigInt {
+    fn sub_assign(&mut self, other: &BigInt) {
+        *self = &*self - other;
+    }
+}
+
+impl MulAssign<&BigInt> for BigInt {
+    fn mul_assign(&mut self, other: &BigInt) {
+        *self = &*self * other;
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => cmp_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Split the magnitude into base 10^9 digits, least significant first
+        let mut digits = Vec::new();
+        let mut magnitude = self.limbs.clone();
+
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = div_rem_magnitude(&magnitude, &[1_000_000_000]);
+
+            digits.push(remainder[0]);
+            magnitude = quotient;
+            trim(&mut magnitude);
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{}", digits.pop().unwrap())?;
+
+        for digit in digits.into_iter().rev() {
+            write!(f, "{:09}", digit)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn i128_round_trip() {
+        for x in [0, 1, -1, 1 << 32, -(1 << 100), i128::MAX, i128::MIN] {
+            assert_eq!(BigInt::from(x).to_i128(), Some(x));
+        }
+
+        assert_eq!((BigInt::from(i128::MAX) + BigInt::from(1)).to_i128(), None);
+        assert_eq!((BigInt::from(i128::MIN) - BigInt::from(1)).to_i128(), None);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        assert_eq!(BigInt::zero().to_be_bytes(), Vec::<u8>::new());
+        assert_eq!(BigInt::from_be_bytes(&[]), BigInt::zero());
+        assert_eq!(BigInt::from_be_bytes(&[0, 0, 0]), BigInt::zero());
+
+        assert_eq!(
+            BigInt::from(0x01_0203_0405i64).to_be_bytes(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(BigInt::from(-256).to_be_bytes(), vec![1, 0]);
+        assert_eq!(BigInt::from_be_bytes(&[0, 0, 1, 0]), BigInt::from(256));
+
+        let big = BigInt::from(i128::MAX) * BigInt::from(i128::MAX) * BigInt::from(12345);
+
+        for value in [
+            BigInt::from(1),
+            BigInt::from(1i128 << 32),
+            BigInt::from(u64::MAX as i128),
+            BigInt::from(i128::MIN),
+            big.clone(),
+            -big,
+        ] {
+            let bytes = value.to_be_bytes();
+            assert_ne!(bytes.first(), Some(&0));
+
+            let magnitude = BigInt::from_be_bytes(&bytes);
+            let round_trip = if value.is_negative() {
+                -magnitude
+            } else {
+                magnitude
+            };
+
+            assert_eq!(round_trip, value);
+        }
+    }
+
+    #[test]
+    fn range() {
+        assert_eq!(
+            BigInt::range(BigInt::from(-2), BigInt::from(3)).collect::<Vec<_>>(),
+            (-2..3).map(BigInt::from).collect::<Vec<_>>()
+        );
+
+        assert_eq!(BigInt::range(BigInt::from(3), BigInt::from(3)).count(), 0);
+        assert_eq!(BigInt::range(BigInt::from(5), BigInt::from(-5)).count(), 0);
+
+        // Crossing the boundary between limbs
+        let start = BigInt::from(u32::MAX as i128 - 1);
+        assert_eq!(
+            BigInt::range(start, BigInt::from(1i128 << 32) + BigInt::from(1)).collect::<Vec<_>>(),
+            vec![
+                BigInt::from(u32::MAX as i128 - 1),
+                BigInt::from(u32::MAX as i128),
+                BigInt::from(1i128 << 32),
+            ]
+        );
+
+        // It's lazy, so huge ranges are fine as long as only a few are taken
+        let huge = BigInt::from(i128::MAX) * BigInt::from(i128::MAX);
+        assert_eq!(
+            BigInt::range(huge.clone(), huge.clone() * BigInt::from(2))
+                .nth(2)
+                .unwrap(),
+            huge + BigInt::from(2)
+        );
+    }
+
+    #[test]
+    fn addition_and_subtraction() {
+        assert_eq!(
+            BigInt::from(u32::MAX as i128) + BigInt::from(1),
+            BigInt::from(1i128 << 32)
+        );
+        assert_eq!(BigInt::from(5) + BigInt::from(-7), BigInt::from(-2));
+        assert_eq!(BigInt::from(-5) - BigInt::from(-5), BigInt::zero());
+        assert_eq!(
+            BigInt::from(1i128 << 64) - BigInt::from(1),
+            BigInt::from(u64::MAX as i128)
+        );
+    }
+
+    #[test]
+    fn multiplication() {
+        let big = BigInt::from(i128::MAX) * BigInt::from(i128::MAX);
+
+        assert_eq!(
+            big.to_string(),
+            "28948022309329048855892746252171976962977213799489202546401021394546514198529"
+        );
+        assert_eq!(BigInt::from(-3) * BigInt::from(7), BigInt::from(-21));
+        assert_eq!(BigInt::from(-3) * BigInt::zero(), BigInt::zero());
+    }
+
+    #[test]
+    fn division() {
+        let a = BigInt::from(i128::MAX) * BigInt::from(12345);
+
+        assert_eq!(
+            a.div_rem(&BigInt::from(12345)),
+            (BigInt::from(i128::MAX), BigInt::zero())
+        );
+        assert_eq!(
+            (a.clone() + BigInt::from(7)).div_rem(&BigInt::from(i128::MAX)),
+            (BigInt::from(12345), BigInt::from(7))
+        );
+
+        // Quotients round towards zero, just like with primitive integers
+        assert_eq!(
+            BigInt::from(-7).div_rem(&BigInt::from(2)),
+            (BigInt::from(-3), BigInt::from(-1))
+        );
+        assert_eq!(
+            BigInt::from(7).div_rem(&BigInt::from(-2)),
+            (BigInt::from(-3), BigInt::from(1))
+        );
+    }
+
+    #[test]
+    fn gcd() {
+        let a = BigInt::from(i128::MAX) * BigInt::from(6);
+        let b = BigInt::from(i128::MAX) * BigInt::from(-4);
+
+        assert_eq!(a.gcd(&b), BigInt::from(i128::MAX) * BigInt::from(2));
+        assert_eq!(BigInt::from(17).gcd(&BigInt::zero()), BigInt::from(17));
+    }
+
+    #[test]
+    fn pow() {
+        assert_eq!(BigInt::from(3).pow(0), BigInt::from(1));
+        assert_eq!(BigInt::zero().pow(0), BigInt::from(1));
+        assert_eq!(BigInt::zero().pow(5), BigInt::zero());
+        assert_eq!(BigInt::from(-2).pow(127), BigInt::from(i128::MIN));
+        assert_eq!(BigInt::from(-3).pow(4), BigInt::from(81));
+
+        let big = BigInt::from(10).pow(60);
+        assert_eq!(
+            big,
+            BigInt::from(10i128.pow(30)) * BigInt::from(10i128.pow(30))
+        );
+        assert_eq!(big.pow(2), BigInt::from(10).pow(120));
+    }
+
+    #[test]
+    fn to_f64() {
+        assert_eq!(BigInt::zero().to_f64(), 0.0);
+        assert_eq!(BigInt::from(-12345).to_f64(), -12345.0);
+        assert_eq!(BigInt::from(i128::MAX).to_f64(), i128::MAX as f64);
+        assert_eq!(BigInt::from(10).pow(300).to_f64(), 1e300);
+        assert_eq!(BigInt::from(-2).pow(1023).to_f64(), -(2f64.powi(1023)));
+        assert_eq!(BigInt::from(2).pow(1024).to_f64(), f64::INFINITY);
+    }
+
+    #[test]
+    fn bit_length_and_parity() {
+        assert_eq!(BigInt::zero().bit_length(), 0);
+        assert!(BigInt::zero().is_even());
+
+        assert_eq!(BigInt::from(1).bit_length(), 1);
+        assert!(!BigInt::from(1).is_even());
+        assert_eq!(BigInt::from(-6).bit_length(), 3);
+        assert!(BigInt::from(-6).is_even());
+        assert_eq!(BigInt::from(u32::MAX as i128).bit_length(), 32);
+
+        // Spanning multiple limbs
+        assert_eq!(BigInt::from(1i128 << 32).bit_length(), 33);
+        assert!(BigInt::from(1i128 << 32).is_even());
+        assert_eq!(BigInt::from(i128::MAX).bit_length(), 127);
+        assert!(!BigInt::from(i128::MIN + 1).is_even());
+
+        let big = BigInt::from(i128::MAX) * BigInt::from(i128::MAX);
+        assert_eq!(big.bit_length(), 254);
+        assert!(!big.is_even());
+        assert!((big + BigInt::from(1)).is_even());
+    }
+
+    #[test]
+    fn isqrt() {
+        assert_eq!(BigInt::zero().isqrt(), BigInt::zero());
+        assert_eq!(BigInt::from(1).isqrt(), BigInt::from(1));
+        assert_eq!(BigInt::from(15).isqrt(), BigInt::from(3));
+        assert_eq!(BigInt::from(16).isqrt(), BigInt::from(4));
+        assert_eq!(
+            BigInt::from(u64::MAX as i128).isqrt(),
+            BigInt::from(u32::MAX as i128)
+        );
+
+        // Way beyond the range of an i128
+        let root = BigInt::from(i128::MAX) * BigInt::from(12345);
+        let square = root.clone() * root.clone();
+
+        assert_eq!(square.isqrt(), root);
+        assert_eq!(
+            (square.clone() - BigInt::from(1)).isqrt(),
+            root.clone() - BigInt::from(1)
+        );
+        assert_eq!((square.clone() + root.clone()).isqrt(), root);
+
+        assert!(square.is_perfect_square());
+        assert!(!(square.clone() + BigInt::from(1)).is_perfect_square());
+        assert!(!(square - BigInt::from(1)).is_perfect_square());
+        assert!(BigInt::zero().is_perfect_square());
+        assert!(!BigInt::from(-4).is_perfect_square());
+    }
+
+    #[test]
+    fn nth_root() {
+        assert_eq!(BigInt::zero().nth_root(3), BigInt::zero());
+        assert_eq!(BigInt::from(26).nth_root(3), BigInt::from(2));
+        assert_eq!(BigInt::from(27).nth_root(3), BigInt::from(3));
+        assert_eq!(BigInt::from(12345).nth_root(1), BigInt::from(12345));
+        assert_eq!(BigInt::from(12345).nth_root(u32::MAX), BigInt::from(1));
+
+        let root = BigInt::from(10).pow(25) + BigInt::from(7);
+
+        for n in [2, 3, 5, 8] {
+            let power = root.pow(n);
+
+            assert_eq!(power.nth_root(n), root);
+            assert_eq!(
+                (&power - &BigInt::from(1)).nth_root(n),
+                &root - &BigInt::from(1)
+            );
+            assert_eq!((&power + &root).nth_root(n), root);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to take the square root of a negative number")]
+    fn isqrt_of_negative_panics() {
+        BigInt::from(-4).isqrt();
+    }
+
+    #[test]
+    fn reference_operators() {
+        let values = [
+            BigInt::zero(),
+            BigInt::from(7),
+            BigInt::from(-3),
+            BigInt::from(i128::MAX) * BigInt::from(12345),
+            BigInt::from(i128::MIN) * BigInt::from(i128::MAX),
+        ];
+
+        for a in &values {
+            assert_eq!(-a, -a.clone());
+
+            for b in &values {
+                assert_eq!(a + b, a.clone() + b.clone());
+                assert_eq!(a - b, a.clone() - b.clone());
+                assert_eq!(a * b, a.clone() * b.clone());
+
+                if !b.is_zero() {
+                    assert_eq!(a / b, a.clone() / b.clone());
+                    assert_eq!(a % b, a.clone() % b.clone());
+                }
+
+                let mut sum = a.clone();
+                sum += b;
+                assert_eq!(sum, a + b);
+
+                let mut difference = a.clone();
+                difference -= b;
+                assert_eq!(difference, a - b);
+
+                let mut product = a.clone();
+                product *= b;
+                assert_eq!(product, a * b);
+            }
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(BigInt::from(-5) < BigInt::from(3));
+        assert!(BigInt::from(-5) < BigInt::from(-3));
+        assert!(BigInt::from(i128::MAX) * BigInt::from(2) > BigInt::from(i128::MAX));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(BigInt::zero().to_string(), "0");
+        assert_eq!(BigInt::from(-42).to_string(), "-42");
+        assert_eq!(BigInt::from(1_000_000_000).to_string(), "1000000000");
+        assert_eq!(BigInt::from(i128::MIN).to_string(), i128::MIN.to_string());
+    }
+}
@@ -0,0 +1,159 @@
+use core::{
+    fmt::Display,
+    ops::{Add, Mul, Sub},
+};
+
+use crate::rational::Rational;
+
+/// A closed interval `[lo, hi]` of rationals, used to rigorously bound the result of a computation whose inputs are
+/// only known to lie within some range (https://en.wikipedia.org/wiki/Interval_arithmetic).
+///
+/// The result of an operation on intervals contains every result of the operation on their elements, but it isn't
+/// necessarily as tight as possible, e.g. `x * x` over `[-1, 1]` gives `[-1, 1]` rather than `[0, 1]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RationalInterval {
+    lo: Rational,
+    hi: Rational,
+}
+
+impl RationalInterval {
+    pub fn new(lo: Rational, hi: Rational) -> Self {
+        assert!(
+            lo <= hi,
+            "interval bounds are out of order: [{}, {}]",
+            lo,
+            hi
+        );
+
+        RationalInterval { lo, hi }
+    }
+
+    /// Create the degenerate interval `[x, x]` containing just `x`.
+    pub fn point(x: Rational) -> Self {
+        RationalInterval {
+            lo: x.clone(),
+            hi: x,
+        }
+    }
+
+    /// Get the lower bound.
+    pub fn lo(&self) -> &Rational {
+        &self.lo
+    }
+
+    /// Get the upper bound.
+    pub fn hi(&self) -> &Rational {
+        &self.hi
+    }
+
+    /// Check whether `x` lies within the interval.
+    pub fn contains(&self, x: Rational) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// Get the sign shared by every value in the interval as `-1`, `0` or `1`, or `None` if the interval contains values
+    /// of different signs.
+    pub fn signum(&self) -> Option<i32> {
+        if self.lo.signum() == self.hi.signum() {
+            Some(self.lo.signum())
+        } else {
+            None
+        }
+    }
+
+    pub fn width(&self) -> Rational {
+        &self.hi - &self.lo
+    }
+}
+
+impl Add for RationalInterval {
+    type Output = RationalInterval;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        RationalInterval {
+            lo: self.lo + rhs.lo,
+            hi: self.hi + rhs.hi,
+        }
+    }
+}
+
+impl Sub for RationalInterval {
+    type Output = RationalInterval;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        // The smallest difference comes from the largest subtrahend, and vice versa
+        RationalInterval {
+            lo: self.lo - rhs.hi,
+            hi: self.hi - rhs.lo,
+        }
+    }
+}
+
+impl Mul for RationalInterval {
+    type Output = RationalInterval;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // Depending on the signs, any of the products of the bounds can be the extremes
+        let products = [
+            &self.lo * &rhs.lo,
+            &self.lo * &rhs.hi,
+            &self.hi * &rhs.lo,
+            &self.hi * &rhs.hi,
+        ];
+
+        RationalInterval {
+            lo: products.iter().min().unwrap().clone(),
+            hi: products.iter().max().unwrap().clone(),
+        }
+    }
+}
+
+impl Display for RationalInterval {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(lo: i32, hi: i32) -> RationalInterval {
+        RationalInterval::new(Rational::from(lo), Rational::from(hi))
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(interval(1, 2) + interval(-3, 5), interval(-2, 7));
+        assert_eq!(interval(1, 2) - interval(-3, 5), interval(-4, 5));
+        assert_eq!(interval(1, 2) * interval(3, 4), interval(3, 8));
+        assert_eq!(interval(-1, 2) * interval(-3, 4), interval(-6, 8));
+        assert_eq!(interval(-2, -1) * interval(-3, -2), interval(2, 6));
+        assert_eq!(
+            interval(1, 3) * RationalInterval::point(Rational::new(-1, 2)),
+            RationalInterval::new(Rational::new(-3, 2), Rational::new(-1, 2))
+        );
+    }
+
+    #[test]
+    fn signs() {
+        assert_eq!(interval(1, 2).signum(), Some(1));
+        assert_eq!(interval(-2, -1).signum(), Some(-1));
+        assert_eq!(interval(0, 0).signum(), Some(0));
+        assert_eq!(interval(-1, 1).signum(), None);
+        assert_eq!(interval(0, 1).signum(), None);
+
+        assert!(interval(-1, 1).contains(Rational::from(0)));
+        assert!(!interval(-1, 1).contains(Rational::new(3, 2)));
+        assert_eq!(interval(-1, 1).width(), Rational::from(2));
+        assert_eq!(interval(-1, 1).lo(), &Rational::from(-1));
+        assert_eq!(interval(-1, 1).hi(), &Rational::from(1));
+        assert_eq!(interval(-1, 1).to_string(), "[-1, 1]");
+    }
+
+    #[test]
+    #[should_panic(expected = "interval bounds are out of order: [2, 1]")]
+    fn out_of_order_bounds_panic() {
+        interval(2, 1);
+    }
+}
@@ -0,0 +1,285 @@
+// The library only needs `alloc`, so `std` is just used for the command line interface and when testing
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{cmp::Ordering, fmt::Display};
+
+use parser::{ParseError, Relation};
+use polynomial::Polynomial;
+use solver::{Interval, Root, RootValue, SolverError, TraceStep};
+
+pub mod bigint;
+pub mod interval;
+pub mod multi_polynomial;
+pub mod parser;
+pub mod polynomial;
+pub mod rational;
+pub mod solver;
+
+/// The solution of a polynomial equation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    /// The equation with everything moved to the left-hand side.
+    pub polynomial: Polynomial,
+    pub degree: u32,
+    pub roots: Roots,
+}
+
+impl Solution {
+    /// Format the equation and its solution set as LaTeX.
+    ///
+    /// ```
+    /// use sym::solve;
+    ///
+    /// let latex = solve("x^2 - 2 = 0").unwrap().to_latex();
+    ///
+    /// assert!(latex.contains("\\sqrt{2}"));
+    /// assert_eq!(latex, r"x^{2} - 2 = 0 \implies x \in \left\{-\sqrt{2}, \sqrt{2}\right\}");
+    /// assert_eq!(solve("2 = 1").unwrap().to_latex(), r"1 = 0 \implies x \in \emptyset");
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let roots = match &self.roots {
+            Roots::Finite(roots) if !roots.is_empty() => format!(
+                "\\left\\{{{}\\right\\}}",
+                roots
+                    .iter()
+                    .map(|root| root.value.to_latex())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Roots::Finite(_) | Roots::NoSolution => String::from("\\emptyset"),
+            Roots::AllReals => String::from("\\mathbb{R}"),
+        };
+
+        format!(
+            "{} = 0 \\implies x \\in {}",
+            self.polynomial.to_latex(),
+            roots
+        )
+    }
+}
+
+/// The solutions of a polynomial equation.
+///
+/// Equations between constants aren't really about `x` at all, so they're either true or false for every `x`:
+///
+/// ```
+/// use sym::{solve, Roots};
+///
+/// assert_eq!(solve("x^2 = x^2").unwrap().roots, Roots::AllReals);
+/// assert_eq!(solve("1 = 2").unwrap().roots, Roots::NoSolution);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Roots {
+    /// The distinct roots in the order the solver found them, tagged by whether they're exact (see
+    /// [`solver::find_roots`]). There may be none, like for `x^4 + 1 = 0`.
+    Finite(Vec<Root<RootValue>>),
+    /// The equation holds for every `x`, like `0 = 0`.
+    AllReals,
+    /// The equation is a contradiction like `5 = 3`.
+    NoSolution,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    Solver(SolverError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Error::Parse(error) => write!(f, "{}", error),
+            Error::Solver(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Error::Parse(error) => Some(error),
+            Error::Solver(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl From<SolverError> for Error {
+    fn from(error: SolverError) -> Self {
+        Error::Solver(error)
+    }
+}
+
+/// Parse and solve a polynomial equation like `x^2 - 4 = 0`.
+///
+/// Only univariate polynomials in `x` are supported, so inputs using any other variable are rejected.
+///
+/// ```
+/// use sym::{rational::Rational, solve, solver::RootValue, Roots};
+///
+/// let solution = solve("x^2 - 4 = 0").unwrap();
+/// let Roots::Finite(roots) = solution.roots else {
+///     panic!("expected finitely many roots");
+/// };
+///
+/// assert_eq!(solution.degree, 2);
+/// assert_eq!(roots.len(), 2);
+/// assert_eq!(roots[0].value, RootValue::Exact(Rational::from(-2)));
+/// assert_eq!(roots[1].value, RootValue::Exact(Rational::from(2)));
+/// assert!(roots.iter().all(|root| root.multiplicity == 1));
+/// ```
+///
+/// Roots that aren't rational are found exactly where possible, and approximated otherwise:
+///
+/// ```
+/// use sym::{solve, Roots};
+///
+/// let Roots::Finite(roots) = solve("x^2 - 2x - 1 = 0").unwrap().roots else {
+///     panic!("expected finitely many roots");
+/// };
+///
+/// assert_eq!(roots[0].value.to_string(), "1 - √2");
+/// assert_eq!(roots[1].value.to_string(), "1 + √2");
+/// ```
+///
+/// Equations that are already factored, like `x(x - 1)(x + 2) = 0`, are solved one factor at a time, so the roots are
+/// listed in the order of the factors.
+///
+/// ```
+/// use sym::{rational::Rational, solve, solver::RootValue, Roots};
+///
+/// let solution = solve("x(x-1)(x+2) = 0").unwrap();
+/// let Roots::Finite(roots) = solution.roots else {
+///     panic!("expected finitely many roots");
+/// };
+///
+/// assert_eq!(solution.polynomial.to_string(), "x^3 + x^2 - 2x");
+/// assert_eq!(
+///     roots.iter().map(|root| root.value.clone()).collect::<Vec<_>>(),
+///     [0, 1, -2].map(|root| RootValue::Exact(Rational::from(root)))
+/// );
+///
+/// // Repeated factors add up to the multiplicity
+/// let Roots::Finite(roots) = solve("x^2 (x - 3)(x^2 - 9) = 0").unwrap().roots else {
+///     panic!("expected finitely many roots");
+/// };
+///
+/// assert_eq!(roots[0].multiplicity, 2);
+/// assert_eq!(roots[1].value, RootValue::Exact(Rational::from(3)));
+/// assert_eq!(roots[1].multiplicity, 2);
+/// assert_eq!(roots[2].value, RootValue::Exact(Rational::from(-3)));
+///
+/// // Factors can also come after a group
+/// let Roots::Finite(roots) = solve("(x-1)(x+1)x = 0").unwrap().roots else {
+///     panic!("expected finitely many roots");
+/// };
+///
+/// assert_eq!(
+///     roots.iter().map(|root| root.value.clone()).collect::<Vec<_>>(),
+///     [1, -1, 0].map(|root| RootValue::Exact(Rational::from(root)))
+/// );
+/// ```
+pub fn solve(input: &str) -> Result<Solution, Error> {
+    Ok(solve_with_trace(input)?.0)
+}
+
+/// Like [`solve`], but also returns the steps taken to find the roots, like the candidates tried by the rational root
+/// theorem and the polynomial left over after dividing out each root.
+///
+/// Equations that are already factored are solved one factor at a time, so only the normalized polynomial is traced.
+///
+/// ```
+/// use sym::solve_with_trace;
+///
+/// let (_, trace) = solve_with_trace("x^2 - 3x + 2 = 0").unwrap();
+///
+/// assert_eq!(trace[0].to_string(), "solve x^2 - 3x + 2 = 0");
+/// assert_eq!(trace[1].to_string(), "try the rational candidates {-1, 1, -2, 2}");
+/// assert_eq!(trace[2].to_string(), "found the root 1");
+/// ```
+pub fn solve_with_trace(input: &str) -> Result<(Solution, Vec<TraceStep>), Error> {
+    let (polynomial, factors) = parser::parse_factored(input)?;
+
+    let mut trace = vec![TraceStep::Normalized(polynomial.clone())];
+
+    let roots = if polynomial.is_zero() {
+        Roots::AllReals
+    } else if polynomial.is_constant() {
+        Roots::NoSolution
+    } else {
+        match &factors {
+            Some(factors) => Roots::Finite(solver::find_roots_of_product(factors)),
+            None => Roots::Finite(solver::find_roots_with_trace(&polynomial, &mut trace)),
+        }
+    };
+
+    let solution = Solution {
+        degree: polynomial.degree(),
+        polynomial,
+        roots,
+    };
+
+    Ok((solution, trace))
+}
+
+/// Parse a polynomial equation and display it in canonical form, with like terms combined and everything moved to the
+/// left-hand side. This is the polynomial `solve` actually works with, so it's useful for checking how the input was
+/// understood.
+///
+/// ```
+/// use sym::normalize_input;
+///
+/// assert_eq!(normalize_input("x^2 - 3x - 5x = x^2 + 2x + 3").unwrap(), "-10x - 3");
+/// assert_eq!(normalize_input("3x^2 + 1 = 2x^2 + 2").unwrap(), "x^2 - 1");
+///
+/// // Products are expanded, just like `solve` does
+/// let input = "(x-1)(x+1)x = 0";
+///
+/// assert_eq!(normalize_input(input).unwrap(), "x^3 - x");
+/// assert_eq!(sym::solve(input).unwrap().polynomial.to_string(), "x^3 - x");
+/// ```
+pub fn normalize_input(input: &str) -> Result<String, Error> {
+    Ok(parser::parse_polynomial_expr(input)?.to_string())
+}
+
+/// Parse and solve a strict polynomial inequality like `x^2 - 4 > 0`, returning the solution set as a union of
+/// disjoint open intervals in ascending order.
+///
+/// ```
+/// use sym::solve_inequality;
+///
+/// let intervals = solve_inequality("x^2 - 4 > 0").unwrap();
+///
+/// assert_eq!(intervals.len(), 2);
+/// assert_eq!(intervals[0].to_string(), "(-inf, -2)");
+/// assert_eq!(intervals[1].to_string(), "(2, inf)");
+///
+/// // The bounds don't have to be rational
+/// let intervals = solve_inequality("x^2 - 2 < 0").unwrap();
+///
+/// assert_eq!(intervals[0].to_string(), "(-√2, √2)");
+/// ```
+pub fn solve_inequality(input: &str) -> Result<Vec<Interval>, Error> {
+    let (polynomial, relation) = parser::parse_relation(input)?;
+
+    let ordering = match relation {
+        Relation::Less => Ordering::Less,
+        Relation::Greater => Ordering::Greater,
+        Relation::Equal => return Err(ParseError::ExpectedInequality.into()),
+    };
+
+    Ok(solver::solve_inequality(&polynomial, ordering))
+}
@@ -0,0 +1,328 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::{
+    fmt::Display,
+    ops::{Add, Mul},
+};
+
+use crate::rational::Rational;
+
+/// A product of variables raised to positive powers, e.g. `x^2 y` is `{"x": 2, "y": 1}`. The empty monomial is `1`.
+pub type Monomial = BTreeMap<String, u32>;
+
+/// A polynomial in any number of variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiPolynomial {
+    terms: BTreeMap<Monomial, Rational>,
+}
+
+impl MultiPolynomial {
+    pub fn new(terms: BTreeMap<Monomial, Rational>) -> Self {
+        let mut poly = MultiPolynomial::zero();
+
+        for (monomial, coeff) in terms {
+            poly.add_term(monomial, coeff);
+        }
+
+        poly
+    }
+
+    /// Create the zero polynomial, which has no terms at all.
+    pub fn zero() -> Self {
+        MultiPolynomial {
+            terms: BTreeMap::new(),
+        }
+    }
+
+    /// Add `coeff` to the coefficient of `monomial`, dropping the term if it cancels out.
+    pub fn add_term(&mut self, monomial: Monomial, coeff: Rational) {
+        // `x^0` is just 1, so zero exponents are dropped to keep monomials canonical
+        let monomial = monomial
+            .into_iter()
+            .filter(|&(_, exponent)| exponent != 0)
+            .collect::<Monomial>();
+
+        let sum = self.get(&monomial) + coeff;
+
+        if sum == 0 {
+            self.terms.remove(&monomial);
+        } else {
+            self.terms.insert(monomial, sum);
+        }
+    }
+
+    /// Get the coefficient associated with `monomial`.
+    pub fn get(&self, monomial: &Monomial) -> Rational {
+        self.terms
+            .get(monomial)
+            .cloned()
+            .unwrap_or_else(|| Rational::from(0))
+    }
+
+    /// Get the total degree, i.e. the largest sum of exponents of any term.
+    pub fn degree(&self) -> u32 {
+        self.terms
+            .keys()
+            .map(|monomial| monomial.values().sum())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the names of all variables which appear in the polynomial, in alphabetical order.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut variables = self
+            .terms
+            .keys()
+            .flat_map(|monomial| monomial.keys().map(String::as_str))
+            .collect::<Vec<_>>();
+
+        variables.sort_unstable();
+        variables.dedup();
+
+        variables
+    }
+
+    /// Evaluate the polynomial with each variable substituted by its value in `assignment`.
+    ///
+    /// Returns `None` if a variable in the polynomial isn't assigned a value.
+    pub fn eval(&self, assignment: &BTreeMap<String, Rational>) -> Option<Rational> {
+        let mut result = Rational::from(0);
+
+        for (monomial, coeff) in &self.terms {
+            let mut term = coeff.clone();
+
+            for (variable, &exponent) in monomial {
+                term = term * assignment.get(variable)?.pow(exponent);
+            }
+
+            result += term;
+        }
+
+        Some(result)
+    }
+
+    /// Iterate over the `(monomial, coefficient)` pairs of all terms, in descending order of total degree and then
+    /// alphabetically by variable.
+    fn terms(&self) -> impl Iterator<Item = (&Monomial, Rational)> {
+        let mut terms = self
+            .terms
+            .iter()
+            .map(|(monomial, coeff)| (monomial, coeff.clone()))
+            .collect::<Vec<_>>();
+
+        terms.sort_unstable_by(|(a, _), (b, _)| {
+            let degree = |monomial: &Monomial| monomial.values().sum::<u32>();
+
+            // Monomials with more of the alphabetically first variable come first, e.g. `x^2 + xy + y^2`
+            degree(b).cmp(&degree(a)).then_with(|| {
+                a.iter()
+                    .zip(b.iter())
+                    .map(|((a_var, a_exp), (b_var, b_exp))| a_var.cmp(b_var).then(b_exp.cmp(a_exp)))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(b.len().cmp(&a.len()))
+            })
+        });
+
+        terms.into_iter()
+    }
+}
+
+impl Add for &MultiPolynomial {
+    type Output = MultiPolynomial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+
+        for (monomial, coeff) in &rhs.terms {
+            result.add_term(monomial.clone(), coeff.clone());
+        }
+
+        result
+    }
+}
+
+impl Add for MultiPolynomial {
+    type Output = MultiPolynomial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl Mul for &MultiPolynomial {
+    type Output = MultiPolynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = MultiPolynomial::zero();
+
+        for (lhs_monomial, lhs_coeff) in &self.terms {
+            for (rhs_monomial, rhs_coeff) in &rhs.terms {
+                let mut monomial = lhs_monomial.clone();
+
+                for (variable, &exponent) in rhs_monomial {
+                    *monomial.entry(variable.clone()).or_insert(0) += exponent;
+                }
+
+                result.add_term(monomial, lhs_coeff * rhs_coeff);
+            }
+        }
+
+        result
+    }
+}
+
+impl Mul for MultiPolynomial {
+    type Output = MultiPolynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl Display for MultiPolynomial {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut first = true;
+
+        for (monomial, coeff) in self.terms() {
+            if first {
+                if coeff.is_negative() {
+                    write!(f, "-")?;
+                }
+            } else if coeff.is_positive() {
+                write!(f, " + ")?;
+            } else {
+                write!(f, " - ")?;
+            }
+
+            first = false;
+
+            if coeff.abs() != 1 || monomial.is_empty() {
+                write!(f, "{}", coeff.abs())?;
+            }
+
+            for (variable, &exponent) in monomial {
+                write!(f, "{}", variable)?;
+
+                if exponent > 1 {
+                    write!(f, "^{}", exponent)?;
+                }
+            }
+        }
+
+        // There are no terms at all
+        if first {
+            write!(f, "0")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monomial(variables: &[(&str, u32)]) -> Monomial {
+        variables
+            .iter()
+            .map(|&(variable, exponent)| (variable.to_string(), exponent))
+            .collect()
+    }
+
+    fn variable(name: &str) -> MultiPolynomial {
+        MultiPolynomial::new(BTreeMap::from([(
+            monomial(&[(name, 1)]),
+            Rational::from(1),
+        )]))
+    }
+
+    #[test]
+    fn eval() {
+        // xy + x^2
+        let poly = MultiPolynomial::new(BTreeMap::from([
+            (monomial(&[("x", 1), ("y", 1)]), Rational::from(1)),
+            (monomial(&[("x", 2)]), Rational::from(1)),
+        ]));
+
+        let assignment = BTreeMap::from([
+            (String::from("x"), Rational::from(3)),
+            (String::from("y"), Rational::new(-1, 2)),
+        ]);
+
+        assert_eq!(poly.eval(&assignment), Some(Rational::new(15, 2)));
+        assert_eq!(
+            poly.eval(&BTreeMap::from([(String::from("x"), Rational::from(3))])),
+            None
+        );
+        assert_eq!(poly.variables(), vec!["x", "y"]);
+        assert_eq!(poly.degree(), 2);
+    }
+
+    #[test]
+    fn add() {
+        let x = variable("x");
+        let y = variable("y");
+
+        assert_eq!(
+            &x + &y,
+            MultiPolynomial::new(BTreeMap::from([
+                (monomial(&[("x", 1)]), Rational::from(1)),
+                (monomial(&[("y", 1)]), Rational::from(1)),
+            ]))
+        );
+
+        // Terms that cancel out are dropped
+        let minus_x = MultiPolynomial::new(BTreeMap::from([(
+            monomial(&[("x", 1)]),
+            Rational::from(-1),
+        )]));
+        assert_eq!(&x + &minus_x, MultiPolynomial::zero());
+    }
+
+    #[test]
+    fn mul() {
+        let x = variable("x");
+        let y = variable("y");
+
+        // (x + y)(x - y) = x^2 - y^2
+        let x_minus_y = MultiPolynomial::new(BTreeMap::from([
+            (monomial(&[("x", 1)]), Rational::from(1)),
+            (monomial(&[("y", 1)]), Rational::from(-1)),
+        ]));
+
+        assert_eq!(
+            (&x + &y) * x_minus_y,
+            MultiPolynomial::new(BTreeMap::from([
+                (monomial(&[("x", 2)]), Rational::from(1)),
+                (monomial(&[("y", 2)]), Rational::from(-1)),
+            ]))
+        );
+
+        // x * (xy + x^2) = x^2 y + x^3
+        let poly = MultiPolynomial::new(BTreeMap::from([
+            (monomial(&[("x", 1), ("y", 1)]), Rational::from(1)),
+            (monomial(&[("x", 2)]), Rational::from(1)),
+        ]));
+
+        assert_eq!(
+            &x * &poly,
+            MultiPolynomial::new(BTreeMap::from([
+                (monomial(&[("x", 2), ("y", 1)]), Rational::from(1)),
+                (monomial(&[("x", 3)]), Rational::from(1)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn display() {
+        let poly = MultiPolynomial::new(BTreeMap::from([
+            (monomial(&[("y", 2)]), Rational::from(-1)),
+            (monomial(&[("x", 1), ("y", 1)]), Rational::from(3)),
+            (monomial(&[("x", 2)]), Rational::from(1)),
+            (monomial(&[("x", 0)]), Rational::new(-1, 2)),
+            (monomial(&[("x", 3), ("z", 1)]), Rational::from(-2)),
+        ]));
+
+        assert_eq!(format!("{}", poly), "-2x^3z + x^2 + 3xy - y^2 - 1/2");
+        assert_eq!(format!("{}", MultiPolynomial::zero()), "0");
+    }
+}
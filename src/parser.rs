@@ -1,129 +1,701 @@
-use std::collections::HashMap;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Display;
 
 use crate::{polynomial::Polynomial, rational::Rational};
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input contains a character that isn't part of any token.
+    UnexpectedCharacter(char),
+    /// A number literal in scientific notation is missing the integer after the `e`.
+    MissingScientificExponent,
+    /// A number literal doesn't fit into a rational.
+    NumberTooLarge,
+    /// The exponentiation operator isn't followed by a number.
+    MissingExponent,
+    /// The exponent isn't a non-negative integer.
+    InvalidExponent(Rational),
+    /// The division operator isn't followed by a number.
+    MissingDivisor,
+    /// A term is divided by zero.
+    DivisionByZero,
+    /// The input contains more than one `=`, `<` or `>`.
+    MultipleRelations,
+    /// An inequality was given where an equation was expected.
+    ExpectedEquation,
+    /// An equation was given where an inequality was expected.
+    ExpectedInequality,
+    /// A modulus is used anywhere but in a trailing `(mod p)` qualifier, or where it isn't supported.
+    UnexpectedModulus,
+    /// The modulus isn't an integer greater than 1, or some coefficient can't be reduced modulo it.
+    InvalidModulus(Rational),
+    /// The input uses a variable other than `x`.
+    UnsupportedVariable(String),
+    /// A coefficient list like `[1, 0, -4]` isn't a non-empty, comma-separated list of numbers.
+    MalformedCoefficientList,
+    /// The input uses a well-known constant like `pi`, which can't be represented as a rational.
+    SymbolicConstant(String),
+    /// A `(` isn't closed by a matching `)`, or the other way around.
+    UnbalancedParentheses,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ParseError::UnexpectedCharacter(c) => write!(f, "unexpected character '{}'", c),
+            ParseError::MissingScientificExponent => {
+                write!(f, "expected integer exponent after 'e' in number literal")
+            }
+            ParseError::NumberTooLarge => write!(f, "number literal is too large"),
+            ParseError::MissingExponent => {
+                write!(f, "expected number after exponentiation operator")
+            }
+            ParseError::InvalidExponent(exponent) => {
+                write!(
+                    f,
+                    "exponents must be non-negative integers, got {}",
+                    exponent
+                )
+            }
+            ParseError::MissingDivisor => write!(f, "expected number after division operator"),
+            ParseError::DivisionByZero => write!(f, "division by zero"),
+            ParseError::MultipleRelations => {
+                write!(f, "expected at most one of '=', '<' or '>'")
+            }
+            ParseError::ExpectedEquation => write!(f, "expected an equation, not an inequality"),
+            ParseError::ExpectedInequality => write!(f, "expected an inequality, not an equation"),
+            ParseError::UnexpectedModulus => {
+                write!(f, "a modulus is only supported as a trailing (mod p)")
+            }
+            ParseError::InvalidModulus(modulus) => write!(f, "cannot reduce modulo {}", modulus),
+            ParseError::UnsupportedVariable(name) => write!(
+                f,
+                "only polynomials in x are supported, found variable '{}'",
+                name
+            ),
+            ParseError::MalformedCoefficientList => {
+                write!(f, "expected a list of numbers like [1, 0, -4]")
+            }
+            ParseError::SymbolicConstant(name) => {
+                write!(f, "symbolic constants like {} are not supported", name)
+            }
+            ParseError::UnbalancedParentheses => write!(f, "unbalanced parentheses"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// How the two sides of an equation or inequality relate to each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    Less,
+    Greater,
+}
+
+/// An error while splitting the input into tokens, with the position (in characters, not bytes) where it happened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LexError {
+    /// The character isn't part of any token.
+    UnexpectedCharacter { character: char, position: usize },
+    /// A number literal in scientific notation is missing the integer after the `e`.
+    MissingScientificExponent { position: usize },
+    /// A number literal doesn't fit into a rational. The position is where the literal starts.
+    NumberTooLarge { position: usize },
+}
+
+impl LexError {
+    /// Get the position in the input where the error happened.
+    pub fn position(&self) -> usize {
+        match *self {
+            LexError::UnexpectedCharacter { position, .. } => position,
+            LexError::MissingScientificExponent { position } => position,
+            LexError::NumberTooLarge { position } => position,
+        }
+    }
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "{} at position {}",
+            ParseError::from(*self),
+            self.position()
+        )
+    }
+}
+
+impl core::error::Error for LexError {}
+
+impl From<LexError> for ParseError {
+    fn from(error: LexError) -> Self {
+        match error {
+            LexError::UnexpectedCharacter { character, .. } => {
+                ParseError::UnexpectedCharacter(character)
+            }
+            LexError::MissingScientificExponent { .. } => ParseError::MissingScientificExponent,
+            LexError::NumberTooLarge { .. } => ParseError::NumberTooLarge,
+        }
+    }
+}
+
+/// Names of constants which are recognized just to be rejected with a clear error, rather than being mistaken for a
+/// product of single-letter variables.
+const SYMBOLIC_CONSTANTS: [&str; 3] = ["pi", "tau", "e"];
+
 #[derive(PartialEq, Eq, Debug, Clone)]
-enum Token {
+pub enum Token {
     Number(Rational),
     Operator(char),
     Symbol(String),
+    /// The exponentiation marker, written as either `^` or `**`.
+    Caret,
 }
 
-fn tokenize(input: &str) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut i = 0;
-    let chars = input.chars().collect::<Vec<_>>();
+impl Display for Token {
+    /// Display the token compactly for debugging, e.g. `Number(3/2)`, `Op(+)` or `Sym(x)`.
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "Number({})", value),
+            Token::Operator(c) => write!(f, "Op({})", c),
+            Token::Symbol(name) => write!(f, "Sym({})", name),
+            Token::Caret => write!(f, "Caret"),
+        }
+    }
+}
 
-    while i < input.len() {
-        let c = chars[i];
+/// Splits the input into tokens lazily, one at a time.
+///
+/// Any Unicode whitespace separates tokens, and a leading byte order mark (as sometimes found in files) is ignored.
+///
+/// After an error, the rest of the input is skipped and no more tokens are produced.
+pub struct Tokenizer {
+    chars: Vec<char>,
+    position: usize,
+}
 
-        match c {
-            '0'..='9' => {
-                let mut number = 0;
+impl Tokenizer {
+    pub fn new(input: &str) -> Self {
+        Tokenizer {
+            // NOTE: positions are indices into `chars` rather than `input`, which is indexed by bytes
+            chars: input
+                .strip_prefix('\u{feff}')
+                .unwrap_or(input)
+                .chars()
+                .collect(),
+            position: 0,
+        }
+    }
 
-                while i < input.len() && ('0'..='9').contains(&chars[i]) {
-                    number = number * 10 + (chars[i] as i32 - '0' as i32);
-                    i += 1;
-                }
+    /// Get the index of the character that will be read next.
+    pub fn position(&self) -> usize {
+        self.position
+    }
 
-                tokens.push(Token::Number(Rational::from(number)));
+    fn peek(&self, offset: usize) -> Option<&char> {
+        self.chars.get(self.position + offset)
+    }
 
-                continue;
+    /// Check whether the input continues with the whole word `word`, i.e. not just a prefix of a longer one.
+    fn peek_word(&self, word: &str) -> bool {
+        let len = word.chars().count();
+
+        self.chars[self.position..]
+            .iter()
+            .take(len)
+            .copied()
+            .eq(word.chars())
+            && !self.peek(len).is_some_and(char::is_ascii_alphabetic)
+    }
+
+    /// Read a run of decimal digits, advancing past them. Returns `None` if the digits don't fit into an `i128`, in
+    /// which case they are still skipped.
+    fn read_integer(&mut self) -> Option<i128> {
+        let mut number = Some(0i128);
+
+        while let Some(&c) = self.peek(0).filter(|c| c.is_ascii_digit()) {
+            number = number
+                .and_then(|number| number.checked_mul(10))
+                .and_then(|number| number.checked_add(c as i128 - '0' as i128));
+            self.position += 1;
+        }
+
+        number
+    }
+
+    fn read_number(&mut self) -> Result<Rational, LexError> {
+        let too_large = LexError::NumberTooLarge {
+            position: self.position,
+        };
+
+        let mut number = Rational::from(self.read_integer().ok_or(too_large)?);
+
+        // Decimal fraction, e.g. `2.5`
+        if self.peek(0) == Some(&'.') {
+            self.position += 1;
+
+            let start = self.position;
+            let fraction = self.read_integer().ok_or(too_large)?;
+            let scale = u32::try_from(self.position - start)
+                .ok()
+                .and_then(|digits| 10i128.checked_pow(digits))
+                .ok_or(too_large)?;
+
+            number += Rational::new(fraction, scale);
+        }
+
+        // Scientific notation, e.g. `1e3` or `2.5e-2`
+        if let Some('e' | 'E') = self.peek(0) {
+            self.position += 1;
+
+            let negative = self.peek(0) == Some(&'-');
+            if let Some('+' | '-') = self.peek(0) {
+                self.position += 1;
+            }
+
+            if !self.peek(0).is_some_and(char::is_ascii_digit) {
+                return Err(LexError::MissingScientificExponent {
+                    position: self.position,
+                });
             }
-            '+' | '-' | '*' | '/' | '^' | '=' => tokens.push(Token::Operator(c)),
-            'x' => tokens.push(Token::Symbol(String::from("x"))),
-            ' ' => (),
-            _ => println!("Unknown: {}", c),
+
+            let scale = self
+                .read_integer()
+                .and_then(|exponent| u32::try_from(exponent).ok())
+                .and_then(|exponent| 10i128.checked_pow(exponent))
+                .map(Rational::from)
+                .ok_or(too_large)?;
+
+            number = if negative {
+                number / scale
+            } else {
+                number * scale
+            };
+        }
+
+        Ok(number)
+    }
+}
+
+impl Iterator for Tokenizer {
+    type Item = Result<Token, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.peek(0).is_some_and(|c| c.is_whitespace()) {
+            self.position += 1;
         }
 
-        i += 1
+        let c = *self.peek(0)?;
+
+        let token = match c {
+            '0'..='9' => match self.read_number() {
+                Ok(number) => return Some(Ok(Token::Number(number))),
+                Err(error) => {
+                    self.position = self.chars.len();
+                    return Some(Err(error));
+                }
+            },
+            // Python-style `**` is an alias for `^`, while a lone `*` stays multiplication.
+            '*' if self.peek(1) == Some(&'*') => {
+                self.position += 1;
+                Token::Caret
+            }
+            '^' => Token::Caret,
+            '+' | '-' | '*' | '/' | '=' | '<' | '>' | '%' | '(' | ')' | '[' | ']' | ',' => {
+                Token::Operator(c)
+            }
+            // `mod` is an alias for `%`, as in `(mod 5)`
+            'm' if self.peek_word("mod") => {
+                self.position += 2;
+                Token::Operator('%')
+            }
+            'a'..='z' | 'A'..='Z' => {
+                if let Some(name) = SYMBOLIC_CONSTANTS.iter().find(|name| self.peek_word(name)) {
+                    self.position += name.chars().count() - 1;
+                    Token::Symbol(name.to_string())
+                } else {
+                    // Variables are single letters, so that e.g. `xy` is read as the product of `x` and `y`
+                    Token::Symbol(c.to_string())
+                }
+            }
+            _ => {
+                let error = LexError::UnexpectedCharacter {
+                    character: c,
+                    position: self.position,
+                };
+
+                self.position = self.chars.len();
+                return Some(Err(error));
+            }
+        };
+
+        self.position += 1;
+
+        Some(Ok(token))
     }
+}
 
-    tokens
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    Ok(Tokenizer::new(input).collect::<Result<Vec<_>, _>>()?)
 }
 
-pub fn parse_polynomial_expr(input: &str) -> Polynomial {
-    let tokens = tokenize(input);
-    let mut i = 0;
+/// Display the tokens of `input` one by one for debugging, ending with `Error(...)` if the input can't be tokenized.
+pub fn dump_tokens(input: &str) -> Vec<String> {
+    Tokenizer::new(input)
+        .map(|token| match token {
+            Ok(token) => token.to_string(),
+            Err(error) => format!("Error({})", error),
+        })
+        .collect()
+}
 
-    let mut coeffs = HashMap::new();
+/// The factors of a product along with their exponents, e.g. `[(x - 1, 2), (x + 1, 1)]` for `(x - 1)^2 (x + 1)`.
+pub type Factors = Vec<(Polynomial, u32)>;
 
-    let mut sign = 1;
-    let mut equals_seen = false;
+/// Parse a polynomial equation (or expression) in `x`, moving everything to the left-hand side.
+pub fn parse_polynomial_expr(input: &str) -> Result<Polynomial, ParseError> {
+    Ok(parse_factored(input)?.0)
+}
 
-    // NOTE: i has to be passed as a mut reference because otherwise it is borrowed for the duration of the closing function which makes borrowck angy
-    let parse_exponent = |i: &mut usize| -> Option<i32> {
-        if tokens.get(*i) == Some(&Token::Operator('^')) {
-            *i += 1;
+/// Parse a polynomial equation (or expression) in `x` like [`parse_polynomial_expr`], also returning its factors if the
+/// left-hand side is a product with at least one group in parentheses and the right-hand side is 0, like
+/// `x(x - 1)(x + 2) = 0` or `2(x - 3)^2 = 0`. A leading constant and powers of `x` are factors of their own.
+///
+/// The returned polynomial is always the product of the factors, so they can be solved one by one instead.
+pub fn parse_factored(input: &str) -> Result<(Polynomial, Option<Factors>), ParseError> {
+    match parse_sides(input)? {
+        (lhs, rhs, Relation::Equal, None, factors) => Ok((lhs - rhs, factors)),
+        (_, _, _, Some(_), _) => Err(ParseError::UnexpectedModulus),
+        _ => Err(ParseError::ExpectedEquation),
+    }
+}
 
-            let exponent = match tokens[*i] {
-                Token::Number(value) => value,
-                _ => panic!("expected number after exponentiation operator"),
-            };
-            *i += 1;
+/// Parse a polynomial equation in `x`, returning its left-hand and right-hand sides separately. A lone expression
+/// without an `=` is treated as being equal to 0.
+pub fn parse_equation(input: &str) -> Result<(Polynomial, Polynomial), ParseError> {
+    match parse_sides(input)? {
+        (lhs, rhs, Relation::Equal, None, _) => Ok((lhs, rhs)),
+        (_, _, _, Some(_), _) => Err(ParseError::UnexpectedModulus),
+        _ => Err(ParseError::ExpectedEquation),
+    }
+}
+
+/// Parse a polynomial equation or inequality in `x`, moving everything to the left-hand side. The relation is then
+/// between the returned polynomial and 0, e.g. `x^2 > 4` becomes `x^2 - 4 > 0`.
+///
+/// A lone expression without any relation is treated as being equal to 0.
+pub fn parse_relation(input: &str) -> Result<(Polynomial, Relation), ParseError> {
+    match parse(input)? {
+        (poly, relation, None) => Ok((poly, relation)),
+        (_, _, Some(_)) => Err(ParseError::UnexpectedModulus),
+    }
+}
 
-            let exponent = exponent
-                .as_integer()
-                .expect("parse_polynomial_expr: exponents currently must be integers");
+/// Parse a polynomial equation in `x` with an optional trailing `(mod p)` qualifier, like `x^2 + 3x + 5 (mod 3)`. If
+/// there is one, the coefficients of the returned polynomial are reduced into `0..p`.
+pub fn parse_modular(input: &str) -> Result<(Polynomial, Option<i64>), ParseError> {
+    match parse(input)? {
+        (poly, Relation::Equal, None) => Ok((poly, None)),
+        (poly, Relation::Equal, Some(modulus)) => {
+            let poly = poly
+                .reduce_mod(modulus)
+                .ok_or(ParseError::InvalidModulus(Rational::from(modulus)))?;
 
-            assert!(
-                exponent >= 0,
-                "parse_polynomial_expr: exponents currently must be non-negative"
-            );
+            Ok((poly, Some(modulus)))
+        }
+        _ => Err(ParseError::ExpectedEquation),
+    }
+}
 
-            return Some(exponent as i32);
+/// Remove a trailing `(mod p)` qualifier from `tokens`, returning `p`.
+fn parse_modulus(tokens: &mut Vec<Token>) -> Result<Option<i64>, ParseError> {
+    let modulus = match tokens.as_slice() {
+        [.., Token::Operator('('), Token::Operator('%'), Token::Number(modulus), Token::Operator(')')] => {
+            modulus.clone()
         }
+        _ => return Ok(None),
+    };
+
+    tokens.truncate(tokens.len() - 4);
+
+    match modulus.as_integer().map(i64::try_from) {
+        Some(Ok(modulus)) if modulus > 1 => Ok(Some(modulus)),
+        _ => Err(ParseError::InvalidModulus(modulus)),
+    }
+}
 
-        None
+/// Parse a list of coefficients like `[1, 0, -4]`, highest degree first, which is an alternative to writing out the
+/// polynomial (`x^2 - 4` in this case).
+fn parse_coefficient_list(tokens: &[Token]) -> Result<Polynomial, ParseError> {
+    let elements = match tokens {
+        [Token::Operator('['), elements @ .., Token::Operator(']')] if !elements.is_empty() => {
+            elements
+        }
+        _ => return Err(ParseError::MalformedCoefficientList),
     };
 
-    while i < tokens.len() {
-        match tokens[i] {
-            Token::Number(value) => {
-                i += 1;
+    let coeffs = elements
+        .split(|token| *token == Token::Operator(','))
+        .map(|element| match element {
+            [Token::Number(value)] | [Token::Operator('+'), Token::Number(value)] => {
+                Ok(value.clone())
+            }
+            [Token::Operator('-'), Token::Number(value)] => Ok(-value),
+            _ => Err(ParseError::MalformedCoefficientList),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-                let coefficient = Rational::from(sign * if equals_seen { -1 } else { 1 }) * value;
+    Ok(Polynomial::from(
+        coeffs
+            .into_iter()
+            .rev()
+            .enumerate()
+            .map(|(degree, coeff)| (degree as u32, coeff))
+            .collect::<Vec<_>>(),
+    ))
+}
 
-                if tokens.get(i) == Some(&Token::Symbol("x".to_string())) {
-                    i += 1;
+/// Parse a relation, moving everything to the left-hand side.
+fn parse(input: &str) -> Result<(Polynomial, Relation, Option<i64>), ParseError> {
+    let (lhs, rhs, relation, modulus, _) = parse_sides(input)?;
 
-                    if let Some(exponent) = parse_exponent(&mut i) {
-                        *coeffs
-                            .entry(exponent as u32)
-                            .or_insert_with(|| Rational::from(0)) += coefficient;
-                    } else {
-                        *coeffs.entry(1).or_insert_with(|| Rational::from(0)) += coefficient;
-                    }
-                } else {
-                    *coeffs.entry(0).or_insert_with(|| Rational::from(0)) += coefficient;
+    Ok((lhs - rhs, relation, modulus))
+}
+
+/// The left-hand and right-hand sides of a relation, along with its modulus and the factors of the left-hand side.
+type Sides = (
+    Polynomial,
+    Polynomial,
+    Relation,
+    Option<i64>,
+    Option<Factors>,
+);
+
+/// Parse a relation into its left-hand and right-hand sides, the latter of which is 0 if there's no relation at all,
+/// along with the factors of the left-hand side if it's a product (see [`parse_factored`]).
+fn parse_sides(input: &str) -> Result<Sides, ParseError> {
+    let mut tokens = tokenize(input)?;
+    let modulus = parse_modulus(&mut tokens)?;
+
+    if tokens.first() == Some(&Token::Operator('[')) {
+        return Ok((
+            parse_coefficient_list(&tokens)?,
+            Polynomial::zero(),
+            Relation::Equal,
+            modulus,
+            None,
+        ));
+    }
+
+    let (lhs, rhs, relation, factors) = parse_terms(&tokens)?;
+
+    Ok((lhs, rhs, relation, modulus, factors))
+}
+
+/// Parse the exponent after a `^` at `tokens[*i]`, if there is one, advancing `i` past it.
+fn parse_exponent(tokens: &[Token], i: &mut usize) -> Result<Option<u32>, ParseError> {
+    if tokens.get(*i) == Some(&Token::Caret) {
+        *i += 1;
+
+        let exponent = match tokens.get(*i) {
+            Some(Token::Number(value)) => value.clone(),
+            _ => return Err(ParseError::MissingExponent),
+        };
+        *i += 1;
+
+        return match exponent.as_integer().map(u32::try_from) {
+            Some(Ok(exponent)) => Ok(Some(exponent)),
+            _ => Err(ParseError::InvalidExponent(exponent)),
+        };
+    }
+
+    Ok(None)
+}
+
+/// Parse the divisor after a `/` at `tokens[*i]`, if there is one, advancing `i` past it. Terms can be divided by a
+/// number or a power of one, e.g. `x^2/2`, `3x/4` or `x/2^3`. Without a `/`, the divisor is just 1.
+fn parse_divisor(tokens: &[Token], i: &mut usize) -> Result<Rational, ParseError> {
+    if tokens.get(*i) == Some(&Token::Operator('/')) {
+        *i += 1;
+
+        let divisor = match tokens.get(*i) {
+            Some(Token::Number(value)) => value.clone(),
+            _ => return Err(ParseError::MissingDivisor),
+        };
+        *i += 1;
+
+        let divisor = divisor.pow(parse_exponent(tokens, i)?.unwrap_or(1));
+
+        if divisor == 0 {
+            return Err(ParseError::DivisionByZero);
+        }
+
+        return Ok(divisor);
+    }
+
+    Ok(Rational::from(1))
+}
+
+/// Parse a term starting at `tokens[*i]` as a product of factors, advancing `i` past it. The factors are `x`, numbers
+/// and parenthesized groups (which may themselves contain groups), each with an optional exponent, like `2x^2 (x - 1)`
+/// or `(x + 1)^2 * 3`. They are multiplied either implicitly or explicitly with `*`, and the product can be divided by
+/// a number, e.g. `3x/4` or `(x + 1)x/2`.
+///
+/// Returns the product multiplied by `sign`, along with all of its factors if there was at least one group.
+fn parse_product(
+    tokens: &[Token],
+    i: &mut usize,
+    sign: i32,
+) -> Result<(Polynomial, Option<Factors>), ParseError> {
+    let constant = |value: Rational| Polynomial::from(vec![(0, value)]);
+
+    let mut factors = Vec::new();
+    let mut grouped = false;
+
+    if sign < 0 {
+        factors.push((constant(Rational::from(sign)), 1));
+    }
+
+    let start_of_term = *i;
+
+    loop {
+        // Any factor but the first can be multiplied explicitly, but then there has to be one
+        let explicit = *i > start_of_term && tokens.get(*i) == Some(&Token::Operator('*'));
+        let start = *i + explicit as usize;
+
+        let factor = match tokens.get(start) {
+            Some(Token::Operator('(')) => {
+                // Find the matching parenthesis, skipping over any nested groups
+                let mut depth = 0;
+                let end = (start..tokens.len())
+                    .find(|&j| {
+                        match tokens[j] {
+                            Token::Operator('(') => depth += 1,
+                            Token::Operator(')') => depth -= 1,
+                            _ => (),
+                        }
+
+                        depth == 0
+                    })
+                    .ok_or(ParseError::UnbalancedParentheses)?;
+
+                let group = &tokens[start + 1..end];
+
+                // A group is just an expression, so it can't relate two sides
+                if let Some(Token::Operator(c)) = group
+                    .iter()
+                    .find(|token| matches!(token, Token::Operator('=' | '<' | '>')))
+                {
+                    return Err(ParseError::UnexpectedCharacter(*c));
                 }
 
-                sign = 1;
+                *i = end + 1;
+                grouped = true;
+
+                parse_terms(group)?.0
+            }
+            Some(Token::Symbol(name)) if name == "x" => {
+                *i = start + 1;
+
+                Polynomial::from(vec![(1, Rational::from(1))])
+            }
+            Some(Token::Number(value)) => {
+                *i = start + 1;
+
+                constant(value.clone())
+            }
+            Some(Token::Operator('/')) if *i > start_of_term && !explicit => {
+                let divisor = parse_divisor(tokens, i)?;
+                factors.push((constant(divisor.reciprocal()), 1));
 
                 continue;
             }
-            Token::Symbol(ref name) if name == "x" => {
-                i += 1;
+            // A dangling `*` doesn't multiply by anything
+            _ if explicit => return Err(ParseError::UnexpectedCharacter('*')),
+            _ => break,
+        };
 
-                let coefficient = Rational::from(sign * if equals_seen { -1 } else { 1 });
+        let exponent = parse_exponent(tokens, i)?.unwrap_or(1);
 
-                if let Some(exponent) = parse_exponent(&mut i) {
-                    *coeffs
-                        .entry(exponent as u32)
-                        .or_insert_with(|| Rational::from(0)) += coefficient;
-                } else {
-                    *coeffs.entry(1).or_insert_with(|| Rational::from(0)) += coefficient;
+        // Trivial factors like `1` in `1x` or `x^0` don't change the product
+        if exponent > 0 && factor != constant(Rational::from(1)) {
+            factors.push((factor, exponent));
+        }
+    }
+
+    let product = factors.iter().fold(
+        constant(Rational::from(1)),
+        |product, (factor, exponent)| product * factor.pow(*exponent),
+    );
+
+    Ok((product, grouped.then_some(factors)))
+}
+
+/// Parse a sum of terms, optionally related to another sum of terms by `=`, `<` or `>`. If the left-hand side is a
+/// single product and the right-hand side is 0, its factors are returned too (see [`parse_product`]).
+fn parse_terms(
+    tokens: &[Token],
+) -> Result<(Polynomial, Polynomial, Relation, Option<Factors>), ParseError> {
+    let mut i = 0;
+
+    // The terms on the left-hand and right-hand sides of the relation
+    let mut sides = [Polynomial::zero(), Polynomial::zero()];
+    // The factors of each term on the left-hand side
+    let mut products = Vec::new();
+
+    let mut sign = 1;
+    let mut equals_seen = false;
+    let mut relation = Relation::Equal;
+
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Symbol(ref name) if SYMBOLIC_CONSTANTS.contains(&name.as_str()) => {
+                return Err(ParseError::SymbolicConstant(name.clone()))
+            }
+            Token::Symbol(ref name) if name != "x" => {
+                return Err(ParseError::UnsupportedVariable(name.clone()))
+            }
+            Token::Number(_) | Token::Symbol(_) | Token::Operator('(') => {
+                let (product, factors) = parse_product(tokens, &mut i, sign)?;
+
+                sides[equals_seen as usize] += product;
+                if !equals_seen {
+                    products.push(factors);
                 }
 
                 sign = 1;
 
                 continue;
             }
+            Token::Operator(')') => return Err(ParseError::UnbalancedParentheses),
+            Token::Operator('%') => return Err(ParseError::UnexpectedModulus),
+            // Multiplication and exponentiation need a factor on their left-hand side
+            Token::Operator('*') => return Err(ParseError::UnexpectedCharacter('*')),
+            Token::Caret => return Err(ParseError::UnexpectedCharacter('^')),
+            Token::Operator(c @ ('[' | ']' | ',')) => {
+                return Err(ParseError::UnexpectedCharacter(c))
+            }
             Token::Operator('-') => {
                 sign = -sign;
             }
-            Token::Operator('=') => {
+            Token::Operator(c @ ('=' | '<' | '>')) => {
+                if equals_seen {
+                    return Err(ParseError::MultipleRelations);
+                }
+
                 equals_seen = true;
+                relation = match c {
+                    '<' => Relation::Less,
+                    '>' => Relation::Greater,
+                    _ => Relation::Equal,
+                };
             }
             _ => (),
         }
@@ -131,5 +703,805 @@ pub fn parse_polynomial_expr(input: &str) -> Polynomial {
         i += 1;
     }
 
-    Polynomial::new(coeffs)
+    let [lhs, rhs] = sides;
+
+    let factors = match products.pop() {
+        Some(factors) if products.is_empty() && relation == Relation::Equal && rhs.is_zero() => {
+            factors
+        }
+        _ => None,
+    };
+
+    Ok((lhs, rhs, relation, factors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parenthesized_products() {
+        let parse = |input| parse_polynomial_expr(input).unwrap();
+
+        assert_eq!(parse("(x+1)(x-1)"), parse("x^2 - 1"));
+        assert_eq!(parse("(x+1) * (x-1)"), parse("x^2 - 1"));
+        assert_eq!(parse("(x+1)^2 (x-1)"), parse("x^3 + x^2 - x - 1"));
+        assert_eq!(parse("2x(x - 3) = -(x + 1)"), parse("2x^2 - 5x + 1"));
+        assert_eq!(parse("x^2/2 (x - 1) + 1"), parse("x^3/2 - x^2/2 + 1"));
+        assert_eq!(
+            parse("((x + 1)(x + 2))^2 - 1 = 3 * (x)"),
+            parse("(x^2 + 3x + 2)^2 - 1 - 3x")
+        );
+        assert_eq!(parse("(x + 1)"), parse("x + 1"));
+
+        assert_eq!(
+            parse_polynomial_expr("(x + 1)(x - 1"),
+            Err(ParseError::UnbalancedParentheses)
+        );
+        assert_eq!(
+            parse_polynomial_expr("x + 1) = 0"),
+            Err(ParseError::UnbalancedParentheses)
+        );
+        assert_eq!(
+            parse_polynomial_expr("(x = 1)"),
+            Err(ParseError::UnexpectedCharacter('='))
+        );
+    }
+
+    #[test]
+    fn factors_after_groups() {
+        let parse = |input| parse_polynomial_expr(input).unwrap();
+
+        // Whatever follows a group multiplies it, rather than starting a new term
+        assert_eq!(parse("(x+1)x"), parse("x^2 + x"));
+        assert_eq!(parse("(x+1)*x"), parse("x^2 + x"));
+        assert_eq!(parse("(x+1)x^2"), parse("x^3 + x^2"));
+        assert_eq!(parse("(x+1)2"), parse("2x + 2"));
+        assert_eq!(parse("(x+1) * 2^3"), parse("8x + 8"));
+        assert_eq!(parse("(x+1)/2"), parse("x/2 + 1/2"));
+        assert_eq!(parse("(x+1)x/2 - 1"), parse("x^2/2 + x/2 - 1"));
+        assert_eq!(parse("(x-1)(x+1)x = 0"), parse("x^3 - x"));
+        assert_eq!(parse("(x-1)(x+1)*x*3 = 0"), parse("3x^3 - 3x"));
+        assert_eq!(parse("-(x+1)2x + 1"), parse("-2x^2 - 2x + 1"));
+        assert_eq!(parse("(x+1)^2 / 4 = x"), parse("x^2/4 - x/2 + 1/4"));
+
+        assert_eq!(
+            crate::solver::solve_univariate_polynomial(&parse("(x+1)2"))
+                .unwrap()
+                .iter()
+                .map(|root| root.value.clone())
+                .collect::<Vec<_>>(),
+            vec![Rational::from(-1)]
+        );
+
+        assert_eq!(
+            parse_polynomial_expr("(x+1)*"),
+            Err(ParseError::UnexpectedCharacter('*'))
+        );
+        assert_eq!(
+            parse_polynomial_expr("(x+1) * = 0"),
+            Err(ParseError::UnexpectedCharacter('*'))
+        );
+        assert_eq!(
+            parse_polynomial_expr("(x+1)/x"),
+            Err(ParseError::MissingDivisor)
+        );
+        assert_eq!(
+            parse_polynomial_expr("(x+1)/0"),
+            Err(ParseError::DivisionByZero)
+        );
+        assert_eq!(
+            parse_polynomial_expr("(x+1)y"),
+            Err(ParseError::UnsupportedVariable(String::from("y")))
+        );
+    }
+
+    #[test]
+    fn token_display() {
+        assert_eq!(
+            dump_tokens("2x^2 - 1"),
+            [
+                "Number(2)",
+                "Sym(x)",
+                "Caret",
+                "Number(2)",
+                "Op(-)",
+                "Number(1)"
+            ]
+        );
+        assert_eq!(
+            dump_tokens("1.5x ** 2 (mod 7)"),
+            [
+                "Number(3/2)",
+                "Sym(x)",
+                "Caret",
+                "Number(2)",
+                "Op(()",
+                "Op(%)",
+                "Number(7)",
+                "Op())"
+            ]
+        );
+        assert_eq!(
+            dump_tokens("x + ?"),
+            [
+                "Sym(x)",
+                "Op(+)",
+                "Error(unexpected character '?' at position 4)"
+            ]
+        );
+    }
+
+    #[test]
+    fn factors() {
+        let linear =
+            |root: i32| Polynomial::from(vec![(0, Rational::from(-root)), (1, Rational::from(1))]);
+
+        let factors = |input| parse_factored(input).unwrap().1;
+
+        assert_eq!(
+            factors("x(x-1)(x+2) = 0"),
+            Some(vec![(linear(0), 1), (linear(1), 1), (linear(-2), 1)])
+        );
+        assert_eq!(
+            factors("(x-1)(x+1)x = 0"),
+            Some(vec![(linear(1), 1), (linear(-1), 1), (linear(0), 1)])
+        );
+        assert_eq!(
+            factors("2x^3 (x - 3)^2 * (x^2 + 1)"),
+            Some(vec![
+                (Polynomial::from(vec![(0, Rational::from(2))]), 1),
+                (linear(0), 3),
+                (linear(3), 2),
+                (parse_polynomial_expr("x^2 + 1").unwrap(), 1),
+            ])
+        );
+
+        assert_eq!(
+            factors("-(x + 1)/2 = 0"),
+            Some(vec![
+                (Polynomial::from(vec![(0, Rational::from(-1))]), 1),
+                (linear(-1), 1),
+                (Polynomial::from(vec![(0, Rational::new(1, 2))]), 1),
+            ])
+        );
+        assert_eq!(
+            factors("((x - 1)(x + 1))^2 = 0"),
+            Some(vec![(parse_polynomial_expr("x^2 - 1").unwrap(), 2)])
+        );
+
+        // The polynomial is the same as without the factors
+        for input in [
+            "x(x-1)(x+2) = 0",
+            "(x-1)(x+1)x = 0",
+            "2x^3 (x - 3)^2 * (x^2 + 1)",
+        ] {
+            assert_eq!(
+                parse_factored(input).unwrap().0,
+                parse_polynomial_expr(input).unwrap()
+            );
+        }
+
+        // Anything else isn't a product
+        assert_eq!(factors("x^2 - 1 = 0"), None);
+        assert_eq!(factors("2x^3 = 0"), None);
+        assert_eq!(factors("(x - 1)(x + 1) = 3"), None);
+        assert_eq!(factors("(x - 1) + 1 = 0"), None);
+        assert_eq!(factors("1 + (x - 1) = 0"), None);
+        assert_eq!(factors("[1, 0, -1]"), None);
+
+        assert_eq!(
+            parse_factored("(x - 1)(x > 1)"),
+            Err(ParseError::UnexpectedCharacter('>'))
+        );
+        assert_eq!(
+            parse_factored("(x - 1)^y = 0"),
+            Err(ParseError::MissingExponent)
+        );
+        assert_eq!(
+            parse_factored("(x - 1)(x + 1) > 0"),
+            Err(ParseError::ExpectedEquation)
+        );
+    }
+
+    #[test]
+    fn equation_sides() {
+        assert_eq!(
+            parse_equation("x^2 = 4").unwrap(),
+            (
+                Polynomial::from(vec![(2, Rational::from(1))]),
+                Polynomial::from(vec![(0, Rational::from(4))])
+            )
+        );
+
+        // Without a relation, the right-hand side is 0
+        let (lhs, rhs) = parse_equation("x^2 - 3x").unwrap();
+        assert_eq!(lhs, parse_polynomial_expr("x^2 - 3x").unwrap());
+        assert!(rhs.is_zero());
+
+        // The sides are kept as written, so like terms are only combined within each side
+        let (lhs, rhs) = parse_equation("x^2 - 3x - 5x = x^2 + 2x + 3").unwrap();
+        assert_eq!(lhs.to_string(), "x^2 - 8x");
+        assert_eq!(rhs.to_string(), "x^2 + 2x + 3");
+        assert_eq!(
+            lhs - rhs,
+            parse_polynomial_expr("x^2 - 3x - 5x = x^2 + 2x + 3").unwrap()
+        );
+
+        assert_eq!(parse_equation("x > 4"), Err(ParseError::ExpectedEquation));
+        assert_eq!(
+            parse_equation("x = 4 (mod 5)"),
+            Err(ParseError::UnexpectedModulus)
+        );
+    }
+
+    #[test]
+    fn double_star_is_exponentiation() {
+        assert_eq!(
+            tokenize("x**2").unwrap(),
+            vec![
+                Token::Symbol(String::from("x")),
+                Token::Caret,
+                Token::Number(Rational::from(2)),
+            ]
+        );
+
+        assert_eq!(
+            parse_polynomial_expr("x**2 - 1 = 0").unwrap(),
+            parse_polynomial_expr("x^2 - 1 = 0").unwrap()
+        );
+    }
+
+    #[test]
+    fn cancelled_terms_are_dropped() {
+        let poly = parse_polynomial_expr("x^2 - 3x - 5x = x^2 + 2x + 3").unwrap();
+
+        assert_eq!(poly.degree(), 1);
+        assert_eq!(poly.get(1), Rational::from(-10));
+        assert_eq!(poly.get(0), Rational::from(-3));
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(
+            parse_polynomial_expr("1e3 x = 0").unwrap().get(1),
+            Rational::from(1000)
+        );
+        assert_eq!(
+            parse_polynomial_expr("2.5e-1 = 0").unwrap().get(0),
+            Rational::new(1, 4)
+        );
+        assert_eq!(
+            parse_polynomial_expr("2.5E+2x^2").unwrap().get(2),
+            Rational::from(250)
+        );
+        assert_eq!(
+            parse_polynomial_expr("0.125").unwrap().get(0),
+            Rational::new(1, 8)
+        );
+    }
+
+    #[test]
+    fn bare_exponent_is_rejected() {
+        assert_eq!(tokenize("2e x"), Err(ParseError::MissingScientificExponent));
+    }
+
+    #[test]
+    fn large_number_literals() {
+        use crate::solver::RootValue;
+
+        assert_eq!(
+            parse_polynomial_expr("123456789012x^2 - 1").unwrap().get(2),
+            Rational::from(123456789012i128)
+        );
+
+        // Large coefficients can still be solved exactly
+        let roots = |input| match crate::solve(input).unwrap().roots {
+            crate::Roots::Finite(roots) => {
+                roots.into_iter().map(|root| root.value).collect::<Vec<_>>()
+            }
+            roots => panic!("expected finitely many roots, got {:?}", roots),
+        };
+        let surd = |coeff| RootValue::Surd {
+            rational: Rational::from(0),
+            coeff: Rational::new(coeff, 61728394506),
+            radicand: 30864197253,
+        };
+
+        assert_eq!(roots("123456789012x^2 - 1 = 0"), [surd(-1), surd(1)]);
+        assert_eq!(
+            roots("123456789012x = 246913578024"),
+            [RootValue::Exact(Rational::from(2))]
+        );
+        assert_eq!(
+            roots("x - 123456789013 = 0"),
+            [RootValue::Exact(Rational::from(123456789013i128))]
+        );
+
+        assert_eq!(
+            tokenize("170141183460469231731687303715884105727"),
+            Ok(vec![Token::Number(Rational::from(i128::MAX))])
+        );
+
+        // Anything that doesn't fit is an error rather than silently wrapping around
+        for input in [
+            "x = 170141183460469231731687303715884105728",
+            "x = 1234567890123456789012345678901234567890123",
+            "x = 0.1234567890123456789012345678901234567890123",
+            "x = 1e40",
+            "x = 1e-40",
+            "x = 2e99999999999",
+        ] {
+            assert_eq!(
+                parse_polynomial_expr(input),
+                Err(ParseError::NumberTooLarge),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(
+            Tokenizer::new("x + 1e40").last(),
+            Some(Err(LexError::NumberTooLarge { position: 4 }))
+        );
+    }
+
+    #[test]
+    fn division() {
+        let poly = parse_polynomial_expr("x^2/2 - 3x/4 + 1/3 = 5/6").unwrap();
+
+        assert_eq!(poly.get(2), Rational::new(1, 2));
+        assert_eq!(poly.get(1), Rational::new(-3, 4));
+        assert_eq!(poly.get(0), Rational::new(-1, 2));
+
+        assert_eq!(
+            parse_polynomial_expr("x/ = 0"),
+            Err(ParseError::MissingDivisor)
+        );
+        assert_eq!(
+            parse_polynomial_expr("x/0 = 0"),
+            Err(ParseError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn relations() {
+        let (poly, relation) = parse_relation("x^2 > 4").unwrap();
+
+        assert_eq!(poly, parse_polynomial_expr("x^2 - 4").unwrap());
+        assert_eq!(relation, Relation::Greater);
+
+        assert_eq!(parse_relation("x < 2x").unwrap().1, Relation::Less);
+        assert_eq!(parse_relation("x = 1").unwrap().1, Relation::Equal);
+        assert_eq!(parse_relation("x").unwrap().1, Relation::Equal);
+
+        assert_eq!(
+            parse_polynomial_expr("x^2 - 4 > 0"),
+            Err(ParseError::ExpectedEquation)
+        );
+        assert_eq!(
+            parse_relation("0 < x < 1"),
+            Err(ParseError::MultipleRelations)
+        );
+    }
+
+    #[test]
+    fn modulus() {
+        assert_eq!(
+            parse_modular("x^2 + 3x + 5 (mod 3)").unwrap(),
+            (parse_polynomial_expr("x^2 + 2").unwrap(), Some(3))
+        );
+        assert_eq!(
+            parse_modular("x^2 - x = 4 (% 7)").unwrap(),
+            (parse_polynomial_expr("x^2 + 6x + 3").unwrap(), Some(7))
+        );
+        assert_eq!(
+            parse_modular("x^2 - 1").unwrap(),
+            (parse_polynomial_expr("x^2 - 1").unwrap(), None)
+        );
+
+        assert_eq!(
+            parse_modular("x (mod 1)"),
+            Err(ParseError::InvalidModulus(Rational::from(1)))
+        );
+        assert_eq!(
+            parse_modular("x/3 (mod 3)"),
+            Err(ParseError::InvalidModulus(Rational::from(3)))
+        );
+        assert_eq!(parse_modular("x mod 3"), Err(ParseError::UnexpectedModulus));
+        assert_eq!(
+            parse_polynomial_expr("x^2 + 1 (mod 2)"),
+            Err(ParseError::UnexpectedModulus)
+        );
+        assert_eq!(
+            parse_polynomial_expr("(x + 1 (mod 2)"),
+            Err(ParseError::UnbalancedParentheses)
+        );
+    }
+
+    #[test]
+    fn coefficient_lists() {
+        assert_eq!(
+            parse_polynomial_expr("[1, 0, -4]").unwrap(),
+            parse_polynomial_expr("x^2 - 4").unwrap()
+        );
+        assert_eq!(
+            crate::solver::solve_univariate_polynomial(
+                &parse_polynomial_expr("[1, 0, -4]").unwrap()
+            )
+            .unwrap()
+            .iter()
+            .map(|root| root.value.clone())
+            .collect::<Vec<_>>(),
+            vec![Rational::from(-2), Rational::from(2)]
+        );
+        assert_eq!(
+            parse_polynomial_expr(" [ -1.5,+2,0 ] ").unwrap(),
+            parse_polynomial_expr("-1.5x^2 + 2x").unwrap()
+        );
+        assert_eq!(
+            parse_polynomial_expr("[7]").unwrap(),
+            Polynomial::from(vec![(0, Rational::from(7))])
+        );
+        assert_eq!(
+            parse_modular("[1, 3, 5] (mod 3)").unwrap(),
+            (parse_polynomial_expr("x^2 + 2").unwrap(), Some(3))
+        );
+
+        for input in ["[]", "[1, 2", "[1,, 2]", "[1, x]", "[1, 2] + x", "[1 2]"] {
+            assert_eq!(
+                parse_polynomial_expr(input),
+                Err(ParseError::MalformedCoefficientList),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_polynomial_expr("x + [1]"),
+            Err(ParseError::UnexpectedCharacter('['))
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_an_error() {
+        assert_eq!(
+            parse_polynomial_expr("x^2 + 3 # 4"),
+            Err(ParseError::UnexpectedCharacter('#'))
+        );
+        assert_eq!(
+            parse_polynomial_expr("x^ = 0"),
+            Err(ParseError::MissingExponent)
+        );
+        assert_eq!(
+            parse_polynomial_expr("x^2.5 = 0"),
+            Err(ParseError::InvalidExponent(Rational::new(5, 2)))
+        );
+        assert_eq!(
+            parse_polynomial_expr("x + y = 0"),
+            Err(ParseError::UnsupportedVariable(String::from("y")))
+        );
+    }
+
+    #[test]
+    fn symbolic_constants_are_rejected() {
+        assert_eq!(
+            parse_polynomial_expr("pi*x = 0"),
+            Err(ParseError::SymbolicConstant(String::from("pi")))
+        );
+        assert_eq!(
+            parse_polynomial_expr("x^2 - e = 0"),
+            Err(ParseError::SymbolicConstant(String::from("e")))
+        );
+        assert_eq!(
+            parse_polynomial_expr("2tau x"),
+            Err(ParseError::SymbolicConstant(String::from("tau")))
+        );
+        assert_eq!(
+            ParseError::SymbolicConstant(String::from("pi")).to_string(),
+            "symbolic constants like pi are not supported"
+        );
+
+        // Only whole words are constants, and `e` in a number is still scientific notation
+        assert_eq!(
+            parse_polynomial_expr("pix = 0"),
+            Err(ParseError::UnsupportedVariable(String::from("p")))
+        );
+        assert_eq!(
+            parse_polynomial_expr("1e2x = 0").unwrap(),
+            parse_polynomial_expr("100x = 0").unwrap()
+        );
+    }
+
+    #[test]
+    fn spacing_does_not_matter() {
+        let expressions: &[&[&str]] = &[
+            &[
+                "x", "^", "2", "-", "3", "x", "-", "5", "x", "=", "x", "^", "2", "+", "2", "x",
+                "+", "3",
+            ],
+            &["x", "^", "2", "+", "5", "x", "+", "6", "=", "0"],
+            &["5", "x", "-", "6", "=", "-", "x", "**", "3"],
+            &["-", "2.5", "x", "^", "3", "+", "1e2", "=", "-", "-", "x"],
+        ];
+
+        // Simple xorshift generator, so the test is deterministic without pulling in a dependency
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for pieces in expressions {
+            let expected = parse_polynomial_expr(&pieces.join(" ")).unwrap();
+
+            assert_eq!(parse_polynomial_expr(&pieces.concat()).unwrap(), expected);
+
+            for _ in 0..100 {
+                let input = pieces
+                    .iter()
+                    .map(|piece| {
+                        let whitespace = [" ", "", "  ", "\t"][(random() % 4) as usize];
+                        format!("{}{}", whitespace, piece)
+                    })
+                    .collect::<String>();
+
+                assert_eq!(
+                    parse_polynomial_expr(&input).unwrap(),
+                    expected,
+                    "input: {:?}",
+                    input
+                );
+            }
+        }
+
+        assert_eq!(
+            parse_polynomial_expr("x^2-3x-5x=x^2+2x+3").unwrap(),
+            parse_polynomial_expr("x^2 - 3x - 5x = x^2 + 2x + 3").unwrap()
+        );
+    }
+
+    #[test]
+    fn any_whitespace_separates_tokens() {
+        let expected = parse_polynomial_expr("x^2 + 2x - 3 = 0").unwrap();
+
+        assert_eq!(
+            parse_polynomial_expr("x^2\t+\t2x\t-\t3\t=\t0").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_polynomial_expr("\r\n x^2 + 2x\r\n- 3 = 0\n").unwrap(),
+            expected
+        );
+        // Non-breaking and ideographic spaces
+        assert_eq!(
+            parse_polynomial_expr("x^2\u{a0}+ 2x\u{3000}- 3 = 0").unwrap(),
+            expected
+        );
+
+        assert_eq!(
+            parse_polynomial_expr("\u{feff}x^2 + 2x - 3 = 0").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Tokenizer::new("\u{feff} x").next(),
+            Some(Ok(Token::Symbol(String::from("x"))))
+        );
+        // Only a leading byte order mark is special
+        assert_eq!(
+            parse_polynomial_expr("x^2\u{feff} = 0"),
+            Err(ParseError::UnexpectedCharacter('\u{feff}'))
+        );
+    }
+
+    #[test]
+    fn non_ascii_input() {
+        assert_eq!(
+            parse_polynomial_expr("x² = 0"),
+            Err(ParseError::UnexpectedCharacter('²'))
+        );
+    }
+
+    #[test]
+    fn caret_is_exponentiation() {
+        assert_eq!(
+            tokenize("x^2").unwrap(),
+            vec![
+                Token::Symbol(String::from("x")),
+                Token::Caret,
+                Token::Number(Rational::from(2)),
+            ]
+        );
+
+        assert!(!tokenize("x^2 - 2^3")
+            .unwrap()
+            .contains(&Token::Operator('^')));
+
+        // Numbers can be raised to a power just like `x`
+        let parse = |input| parse_polynomial_expr(input).unwrap();
+
+        assert_eq!(parse("x^2 + 2^3 = 0"), parse("x^2 + 8"));
+        assert_eq!(parse("2^2 x = 8"), parse("4x - 8"));
+        assert_eq!(parse("x = 10^3/2^3"), parse("x - 125"));
+        assert_eq!(parse("-2^2 x^2"), parse("-4x^2"));
+
+        for input in ["^2 = 0", "x + ^2 = 0", "x = ^2"] {
+            assert_eq!(
+                parse_polynomial_expr(input),
+                Err(ParseError::UnexpectedCharacter('^')),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_polynomial_expr("2^x = 0"),
+            Err(ParseError::MissingExponent)
+        );
+    }
+
+    #[test]
+    fn single_star_is_multiplication() {
+        assert_eq!(
+            tokenize("2*x").unwrap(),
+            vec![
+                Token::Number(Rational::from(2)),
+                Token::Operator('*'),
+                Token::Symbol(String::from("x")),
+            ]
+        );
+
+        let parse = |input| parse_polynomial_expr(input).unwrap();
+
+        // Both sides of a `*` are multiplied, whether they're numbers, `x` or groups
+        assert_eq!(parse("x*x = 4"), parse("x^2 - 4"));
+        assert_eq!(parse("x * 3 = 6"), parse("3x - 6"));
+        assert_eq!(parse("2 * 3 = x"), parse("-x + 6"));
+        assert_eq!(parse("2 * x * x^2 * 1/2"), parse("x^3"));
+        assert_eq!(parse("x * (x + 1) * 2"), parse("2x^2 + 2x"));
+        assert_eq!(
+            crate::solver::solve_univariate_polynomial(&parse("x*x = 4")).unwrap()[1].value,
+            Rational::from(2)
+        );
+
+        // There has to be something on both sides
+        for input in ["3 + * x = 0", "* x = 0", "x * = 0", "2x *", "x = 3 *"] {
+            assert_eq!(
+                parse_polynomial_expr(input),
+                Err(ParseError::UnexpectedCharacter('*')),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn fractional_right_hand_sides() {
+        let parse = |input| parse_polynomial_expr(input).unwrap();
+        let poly = |terms: Vec<(u32, Rational)>| Polynomial::from(terms);
+
+        assert_eq!(
+            parse("x^2 = -1/4 + 1/2"),
+            poly(vec![(2, Rational::from(1)), (0, Rational::new(-1, 4))])
+        );
+        assert_eq!(
+            parse("x^2 = -3/2"),
+            poly(vec![(2, Rational::from(1)), (0, Rational::new(3, 2))])
+        );
+        assert_eq!(
+            parse("x = 1.5"),
+            poly(vec![(1, Rational::from(1)), (0, Rational::new(-3, 2))])
+        );
+        assert_eq!(
+            parse("x = -0.5"),
+            poly(vec![(1, Rational::from(1)), (0, Rational::new(1, 2))])
+        );
+        assert_eq!(parse("x^2 - 1/2 = -1/4"), parse("x^2 - 1/4"));
+        assert_eq!(parse("x^2 = - -1/4"), parse("x^2 - 1/4"));
+        assert_eq!(parse("-x = -(1/2)"), parse("-x + 1/2"));
+
+        // Fractional coefficients of `x` on the right-hand side, written either way around
+        assert_eq!(parse("2x = -3/4x + 1/2"), parse("11x/4 - 1/2"));
+        assert_eq!(parse("2x = -3x/4 + 1/2"), parse("11x/4 - 1/2"));
+        assert_eq!(parse("x^2 = -1/4x^2 + 1"), parse("5x^2/4 - 1"));
+        assert_eq!(parse("0 = 1/2 * x^2 - 2.5x"), parse("-x^2/2 + 5x/2"));
+    }
+
+    #[test]
+    fn unit_coefficient_spellings() {
+        for degree in 0..=3 {
+            let spellings = match degree {
+                0 => vec!["1", "1x^0", "1 * x^0", "x^0"],
+                1 => vec!["x", "1x", "1 * x", "x^1", "1x^1", "1*x^1"],
+                _ => vec![
+                    "x^{n}",
+                    "1x^{n}",
+                    "1 * x^{n}",
+                    "1*x^{n}",
+                    "x ** {n}",
+                    "1 * x**{n}",
+                ],
+            };
+
+            // Every spelling should agree with the explicit `1x^n`, whatever comes before it
+            for (sign, coeff) in [("", 1i32), ("-", -1), ("0 - ", -1), ("2x^5 + ", 1)] {
+                let expected =
+                    parse_polynomial_expr(&format!("{}{}x^{}", sign, coeff.abs(), degree)).unwrap();
+
+                assert_eq!(expected.get(degree), Rational::from(coeff));
+
+                for spelling in &spellings {
+                    let input = format!("{}{}", sign, spelling.replace("{n}", &degree.to_string()));
+
+                    assert_eq!(
+                        parse_polynomial_expr(&input).unwrap(),
+                        expected,
+                        "{}",
+                        input
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tokenizer_is_lazy() {
+        let mut tokenizer = Tokenizer::new("3x^2 - 1.5 = 0");
+
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Rational::from(3)))));
+        assert_eq!(tokenizer.position(), 1);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Symbol(String::from("x")))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Caret)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Rational::from(2)))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Operator('-'))));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Rational::new(3, 2))))
+        );
+        assert_eq!(tokenizer.position(), 10);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Operator('='))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Rational::from(0)))));
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    #[test]
+    fn lex_error_positions() {
+        // Tokenizing stops at the first unexpected character, so the `#` after the `²` is never reached
+        let mut tokenizer = Tokenizer::new("x² + #");
+
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Symbol(String::from("x")))));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(LexError::UnexpectedCharacter {
+                character: '²',
+                position: 1
+            }))
+        );
+        // Nothing more is produced after an error
+        assert_eq!(tokenizer.next(), None);
+
+        let errors = Tokenizer::new("x + #")
+            .filter_map(Result::err)
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].position(), 4);
+        assert_eq!(
+            errors[0].to_string(),
+            "unexpected character '#' at position 4"
+        );
+
+        // Positions count characters, so the multi-byte non-breaking space doesn't shift the `#`
+        assert_eq!(
+            Tokenizer::new("x\u{a0}+ #").last(),
+            Some(Err(LexError::UnexpectedCharacter {
+                character: '#',
+                position: 4
+            }))
+        );
+
+        assert_eq!(
+            Tokenizer::new("2x + 1e").last(),
+            Some(Err(LexError::MissingScientificExponent { position: 7 }))
+        );
+    }
 }
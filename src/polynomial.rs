@@ -1,28 +1,96 @@
-use std::{collections::HashMap, fmt::Display};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
 
-use crate::rational::Rational;
+use crate::{bigint::BigInt, interval::RationalInterval, rational::Rational, solver};
 
-#[derive(Debug)]
+#[cfg(test)]
+std::thread_local! {
+    /// The number of calls to [`Polynomial::diff`] on the current thread, so that tests can check how often derivatives
+    /// are computed.
+    pub(crate) static DIFF_CALLS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+#[derive(Clone, Debug)]
 pub struct Polynomial {
-    coeffs: HashMap<u32, Rational>,
+    coeffs: BTreeMap<u32, Rational>,
     degree: u32,
 }
 
 impl Polynomial {
-    pub fn new(coeffs: HashMap<u32, Rational>) -> Self {
+    pub fn new(coeffs: BTreeMap<u32, Rational>) -> Self {
         if coeffs.is_empty() {
             panic!("polynomial must have at least 1 coefficient")
         }
 
+        // Zero leading coefficients don't count towards the degree
         Polynomial {
-            degree: *coeffs.keys().max().unwrap(),
+            degree: coeffs
+                .iter()
+                .rev()
+                .find(|(_, coeff)| !coeff.is_zero())
+                .map_or(0, |(&degree, _)| degree),
             coeffs,
         }
     }
 
+    /// Create the zero polynomial, which has no terms at all.
+    pub fn zero() -> Self {
+        Polynomial {
+            coeffs: BTreeMap::new(),
+            degree: 0,
+        }
+    }
+
+    /// Add `coeff` to the coefficient of the `degree`-th term, dropping the term if it cancels out.
+    pub fn add_term(&mut self, degree: u32, coeff: Rational) {
+        let sum = self.get(degree) + coeff;
+
+        if sum.is_zero() {
+            self.coeffs.remove(&degree);
+        } else {
+            self.coeffs.insert(degree, sum);
+        }
+
+        self.degree = self.coeffs.keys().next_back().copied().unwrap_or(0);
+    }
+
     /// Get the coefficient associated with the `degree`-th term.
     pub fn get(&self, degree: u32) -> Rational {
-        *self.coeffs.get(&degree).unwrap_or(&Rational::from(0))
+        self.coeffs
+            .get(&degree)
+            .cloned()
+            .unwrap_or_else(|| Rational::from(0))
+    }
+
+    /// Iterate over the `(degree, coefficient)` pairs of all non-zero terms, in descending order of degree.
+    pub fn terms(&self) -> impl Iterator<Item = (u32, Rational)> + '_ {
+        // The coefficients are stored in ascending order of degree, so they just have to be walked backwards
+        self.coeffs
+            .iter()
+            .rev()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|(&degree, coeff)| (degree, coeff.clone()))
+    }
+
+    /// Apply `f` to every non-zero coefficient, dropping the terms that become zero.
+    pub fn map_coeffs<F: Fn(Rational) -> Rational>(&self, f: F) -> Polynomial {
+        let mut mapped = Polynomial::zero();
+
+        for (degree, coeff) in self.terms() {
+            mapped.add_term(degree, f(coeff));
+        }
+
+        mapped
     }
 
     /// Evaluate the polynomial at a given value `x` using rational arithmetic.
@@ -30,106 +98,1206 @@ impl Polynomial {
         let mut result = Rational::from(0);
 
         for degree in 0..=self.degree() {
-            let coeff = self.get(degree as u32);
+            let coeff = self.get(degree);
+
+            result += &coeff * &x.pow(degree);
+        }
+
+        result
+    }
+
+    /// Evaluate the polynomial at a given value `x` using floating-point arithmetic.
+    pub fn eval_f64(&self, x: f64) -> f64 {
+        // Use Horner's method (https://en.wikipedia.org/wiki/Horner%27s_method)
+        (0..=self.degree())
+            .rev()
+            .fold(0.0, |result, degree| result * x + self.get(degree).to_f64())
+    }
+
+    /// Evaluate the polynomial at each of `xs` using rational arithmetic.
+    pub fn eval_all(&self, xs: &[Rational]) -> Vec<Rational> {
+        // Look up every coefficient once up front, highest degree first, rather than once per point
+        let coeffs = (0..=self.degree())
+            .rev()
+            .map(|degree| self.get(degree))
+            .collect::<Vec<_>>();
+
+        xs.iter()
+            .map(|x| {
+                coeffs
+                    .iter()
+                    .fold(Rational::from(0), |result, coeff| &(&result * x) + coeff)
+            })
+            .collect()
+    }
+
+    /// Evaluate the polynomial at each of `xs` using floating-point arithmetic.
+    pub fn eval_all_f64(&self, xs: &[f64]) -> Vec<f64> {
+        let coeffs = (0..=self.degree())
+            .rev()
+            .map(|degree| self.get(degree).to_f64())
+            .collect::<Vec<_>>();
+
+        xs.iter()
+            .map(|&x| coeffs.iter().fold(0.0, |result, &coeff| result * x + coeff))
+            .collect()
+    }
+
+    /// Evaluate the polynomial over a whole interval of `x` values, giving an interval which is guaranteed to contain
+    /// every value the polynomial takes on it (but might be wider than necessary).
+    ///
+    /// If the result doesn't contain 0, the polynomial definitely has no roots in the interval.
+    pub fn eval_interval(&self, x: RationalInterval) -> RationalInterval {
+        (0..=self.degree()).rev().fold(
+            RationalInterval::point(Rational::from(0)),
+            |result, degree| result * x.clone() + RationalInterval::point(self.get(degree)),
+        )
+    }
+
+    /// Evaluate the polynomial at a big integer `x`, with all intermediate values computed exactly as big integers.
+    pub fn eval_bigint(&self, x: &BigInt) -> Rational {
+        // Scale all the coefficients to integers first, so that Horner's method can be done with integers only
+        // The scaling is done with big integers as well, as the LCM of coprime denominators quickly overflows
+        let denom = self.terms().fold(BigInt::from(1), |denom, (_, coeff)| {
+            let coeff_denom = coeff.denom();
+
+            &(&denom / &denom.gcd(&coeff_denom)) * &coeff_denom
+        });
+
+        let numer = (0..=self.degree())
+            .rev()
+            .fold(BigInt::zero(), |result, degree| {
+                let coeff = self.get(degree);
+
+                &result * x + coeff.numer() * (&denom / &coeff.denom())
+            });
+
+        Rational::from_bigints(numer, denom)
+    }
+
+    /// Evaluate both the polynomial and its derivative at a given value `x` in a single pass, returning `(p(x), p'(x))`.
+    pub fn eval_with_derivative(&self, x: Rational) -> (Rational, Rational) {
+        // Horner's method, where the derivative is accumulated alongside the value by the product rule
+        (0..=self.degree()).rev().fold(
+            (Rational::from(0), Rational::from(0)),
+            |(value, derivative), degree| {
+                (
+                    &(&value * &x) + &self.get(degree),
+                    &(&derivative * &x) + &value,
+                )
+            },
+        )
+    }
+
+    /// Get all real roots of the polynomial as floating-point approximations, sorted in ascending order and repeated
+    /// according to their multiplicity if they're rational.
+    ///
+    /// Rational roots are found exactly and converted, while the rest are approximated numerically.
+    pub fn roots_f64(&self) -> Vec<f64> {
+        let mut roots = Vec::new();
+        let mut remaining = self.clone();
+
+        for root in solver::rational_roots(self).unwrap_or_default() {
+            roots.push(root.to_f64());
+
+            // Deflate the polynomial by the root so that the numerical search only has to deal with the irrational ones
+            remaining = remaining.synthetic_div(root).0;
+        }
+
+        roots.append(&mut solver::real_roots_f64(&remaining));
+        roots.sort_by(f64::total_cmp);
+
+        roots
+    }
+
+    /// Divide the polynomial by `divisor` using polynomial long division, returning the quotient and the remainder.
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        let divisor_degree = divisor.degree();
+        let divisor_leading = divisor.get(divisor_degree);
+
+        if divisor_leading.is_zero() {
+            panic!("cannot divide by the zero polynomial");
+        }
+
+        let mut quotient = BTreeMap::new();
+        let mut remainder = self.coeffs.clone();
+        remainder.retain(|_, coeff| !coeff.is_zero());
+
+        while let Some(&degree) = remainder.keys().next_back() {
+            if degree < divisor_degree {
+                break;
+            }
+
+            // Cancel out the leading term of the remainder
+            let factor = &remainder[&degree] / &divisor_leading;
+
+            for (&divisor_degree_i, divisor_coeff) in &divisor.coeffs {
+                let target = degree - divisor_degree + divisor_degree_i;
+                let coeff = remainder
+                    .get(&target)
+                    .cloned()
+                    .unwrap_or_else(|| Rational::from(0))
+                    - &factor * divisor_coeff;
+
+                if coeff.is_zero() {
+                    remainder.remove(&target);
+                } else {
+                    remainder.insert(target, coeff);
+                }
+            }
+
+            quotient.insert(degree - divisor_degree, factor);
+        }
+
+        // Represent the zero polynomial as a lone zero constant term
+        for coeffs in [&mut quotient, &mut remainder] {
+            if coeffs.is_empty() {
+                coeffs.insert(0, Rational::from(0));
+            }
+        }
+
+        (Polynomial::new(quotient), Polynomial::new(remainder))
+    }
+
+    /// Divide the polynomial by the linear factor `x - root` using synthetic division, returning the quotient and the
+    /// remainder, which is just `p(root)`. This is a faster special case of `div_rem`.
+    pub fn synthetic_div(&self, root: Rational) -> (Polynomial, Rational) {
+        let mut quotient = Polynomial::zero();
+        let mut carry = Rational::from(0);
+
+        // Horner's method, where the intermediate values are the coefficients of the quotient
+        for degree in (1..=self.degree()).rev() {
+            carry = &(&carry * &root) + &self.get(degree);
+            quotient.add_term(degree - 1, carry.clone());
+        }
+
+        (quotient, &(&carry * &root) + &self.get(0))
+    }
+
+    /// Factor the polynomial over the rationals as far as the rational root theorem allows.
+    ///
+    /// Returns the leading coefficient along with the monic factors and their multiplicities: a linear factor `x - r` for
+    /// every rational root `r`, followed by the remaining part that couldn't be factored any further (if any). Returns
+    /// `None` if the polynomial has non-integer coefficients, or if they're too large to be factored (see
+    /// [`solver::rational_roots`]).
+    pub fn factor(&self) -> Option<(Rational, Vec<(Polynomial, u32)>)> {
+        let leading = self.get(self.degree());
+        let mut factors: Vec<(Polynomial, u32)> = Vec::new();
+        let mut remaining = self.clone();
+
+        for root in solver::rational_roots(self)? {
+            let factor = Polynomial::new(BTreeMap::from([(0, -&root), (1, Rational::from(1))]));
+            remaining = remaining.synthetic_div(root).0;
+
+            // Roots are repeated according to their multiplicity, so equal roots are always adjacent
+            match factors.last_mut() {
+                Some((last, multiplicity)) if *last == factor => *multiplicity += 1,
+                _ => factors.push((factor, 1)),
+            }
+        }
+
+        if remaining.degree() > 0 {
+            factors.push((remaining.monic(), 1));
+        }
+
+        Some((leading, factors))
+    }
+
+    /// Get just the quotient of dividing by `divisor`, see [`Polynomial::div_rem`].
+    pub fn quotient(&self, divisor: &Polynomial) -> Polynomial {
+        self.div_rem(divisor).0
+    }
+
+    /// Get just the remainder of dividing by `divisor`, see [`Polynomial::div_rem`].
+    pub fn remainder(&self, divisor: &Polynomial) -> Polynomial {
+        self.div_rem(divisor).1
+    }
+
+    /// Check whether the polynomial divides `other` exactly, i.e. without a remainder.
+    ///
+    /// Only the zero polynomial is divisible by the zero polynomial, while every polynomial is divisible by non-zero
+    /// constants.
+    pub fn divides(&self, other: &Polynomial) -> bool {
+        if self.is_zero() {
+            return other.is_zero();
+        }
+
+        other.remainder(self).is_zero()
+    }
+
+    /// Get the monic greatest common divisor of two polynomials.
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        // Use Euclidean algorithm to find the GCD (https://en.wikipedia.org/wiki/Polynomial_greatest_common_divisor#Euclid's_algorithm)
+        let mut a = self.clone();
+        let mut b = other.clone();
+
+        while b.coeffs.values().any(|coeff| !coeff.is_zero()) {
+            let t = a.remainder(&b);
+            a = b;
+            b = t;
+        }
+
+        // gcd(0, 0) = 0 can't be made monic
+        if a.get(a.degree()) == 0 {
+            return a;
+        }
+
+        a.monic()
+    }
+
+    /// Get the resultant of two polynomials, which is zero iff they share a (possibly complex) root.
+    pub fn resultant(&self, other: &Polynomial) -> Rational {
+        // Use the Euclidean algorithm (https://en.wikipedia.org/wiki/Resultant#Computation), based on
+        // res(a, b) = (-1)^(deg a * deg b) * lc(b)^(deg a - deg r) * res(b, r) where r = a mod b
+        let mut a = self.clone();
+        let mut b = other.clone();
+        let mut result = Rational::from(1);
+
+        loop {
+            if a.is_zero() || b.is_zero() {
+                return Rational::from(0);
+            }
+
+            let (m, n) = (a.degree(), b.degree());
+
+            // res(a, c) = c^deg a for a constant c, and vice versa
+            if n == 0 {
+                return result * b.get(0).pow(m);
+            }
+
+            if m == 0 {
+                return result * a.get(0).pow(n);
+            }
+
+            let r = a.remainder(&b);
+
+            if r.is_zero() {
+                return Rational::from(0);
+            }
+
+            if m % 2 == 1 && n % 2 == 1 {
+                result = -result;
+            }
+
+            result = result * b.get(n).pow(m - r.degree());
+
+            a = b;
+            b = r;
+        }
+    }
+
+    /// Get the discriminant of the polynomial, which is zero iff it has a repeated root, e.g. `b^2 - 4ac` for quadratics.
+    ///
+    /// Constants have no roots to compare, so their discriminant is 1, except for the zero polynomial whose is 0.
+    pub fn discriminant(&self) -> Rational {
+        // disc(p) = (-1)^(n (n - 1) / 2) / lc(p) * res(p, p') (https://en.wikipedia.org/wiki/Discriminant#Formal_definition)
+        let degree = self.degree();
+
+        if degree == 0 {
+            return if self.is_zero() {
+                Rational::from(0)
+            } else {
+                Rational::from(1)
+            };
+        }
+
+        let sign = if (degree * (degree - 1) / 2).is_multiple_of(2) {
+            1
+        } else {
+            -1
+        };
+
+        Rational::from(sign) * self.resultant(&self.diff()) / self.get(degree)
+    }
+
+    /// Check whether the polynomial has no repeated roots, i.e. it shares no common factor with its derivative.
+    pub fn is_squarefree(&self) -> bool {
+        // Non-zero constants trivially don't have any (repeated) roots
+        if self.degree() == 0 {
+            return self.get(0) != 0;
+        }
+
+        self.gcd(&self.diff()).degree() == 0
+    }
+
+    /// Split the polynomial into monic square-free factors `a_1, a_2, ...` along with their multiplicities, such that
+    /// it's `a_1 a_2^2 a_3^3 ...` up to its leading coefficient. The factors share no roots, so every root of `a_i` has
+    /// multiplicity exactly `i`. Constant polynomials have no factors.
+    pub fn squarefree_decomposition(&self) -> Vec<(Polynomial, u32)> {
+        let mut factors = Vec::new();
+
+        if self.degree() == 0 {
+            return factors;
+        }
+
+        // Yun's algorithm (https://en.wikipedia.org/wiki/Square-free_polynomial#Yun's_algorithm)
+        let derivative = self.diff();
+        let repeated = self.gcd(&derivative);
+        let mut b = self.quotient(&repeated);
+        let mut d = derivative.quotient(&repeated) - b.diff();
+        let mut multiplicity = 1;
 
-            result += coeff * x.pow(degree as u32);
+        while b.degree() > 0 {
+            let factor = b.gcd(&d);
+            b = b.quotient(&factor);
+            d = d.quotient(&factor) - b.diff();
+
+            if factor.degree() > 0 {
+                factors.push((factor, multiplicity));
+            }
+
+            multiplicity += 1;
+        }
+
+        factors
+    }
+
+    /// Get the multiplicity of `x` as a root of the polynomial, or 0 if it isn't a root at all.
+    pub fn multiplicity_at(&self, x: Rational) -> u32 {
+        let mut multiplicity = 0;
+
+        // Count the number of derivatives (starting with the polynomial itself) that vanish (are 0) at `x`. Only the zero
+        // polynomial could vanish more than `degree` times, in which case the search is cut off.
+        while multiplicity < self.degree() && self.derivative_at(x.clone(), multiplicity) == 0 {
+            multiplicity += 1;
+        }
+
+        multiplicity
+    }
+
+    /// Evaluate the `order`-th derivative (wrt. `x`) at a given value `x`, without computing the derivative itself.
+    pub fn derivative_at(&self, x: Rational, order: u32) -> Rational {
+        let mut result = Rational::from(0);
+
+        // The `order`-th derivative of `x^n` is `n (n - 1) ... (n - order + 1) x^(n - order)`, using the falling
+        // factorial (https://en.wikipedia.org/wiki/Falling_and_rising_factorials)
+        for (degree, coeff) in self.terms().filter(|&(degree, _)| degree >= order) {
+            let falling_factorial = (degree - order + 1..=degree)
+                .fold(Rational::from(1), |product, factor| {
+                    product * Rational::from(factor)
+                });
+
+            result += coeff * falling_factorial * x.pow(degree - order);
         }
 
         result
     }
 
+    /// Get the companion matrix of the monic version of the polynomial, as a list of rows. Its eigenvalues are exactly
+    /// the roots of the polynomial (https://en.wikipedia.org/wiki/Companion_matrix).
+    ///
+    /// For `x^n + c_(n-1) x^(n-1) + ... + c_0`, the subdiagonal is all ones and the last column is `-c_0, ..., -c_(n-1)`.
+    /// Constant polynomials (including the zero polynomial) have no roots, and give an empty matrix.
+    pub fn companion_matrix(&self) -> Vec<Vec<Rational>> {
+        // The zero polynomial can't be made monic
+        if self.degree() == 0 {
+            return vec![];
+        }
+
+        let degree = self.degree() as usize;
+        let monic = self.monic();
+
+        (0..degree)
+            .map(|row| {
+                let mut entries = vec![Rational::from(0); degree];
+
+                if row > 0 {
+                    entries[row - 1] = Rational::from(1);
+                }
+
+                entries[degree - 1] = -monic.get(row as u32);
+
+                entries
+            })
+            .collect()
+    }
+
+    /// Check whether one polynomial is a non-zero constant multiple of the other, like `2x^2 - 2` and `x^2 - 1`. They
+    /// then have exactly the same roots.
+    pub fn equal_up_to_scalar(&self, other: &Polynomial) -> bool {
+        if self.is_zero() || other.is_zero() {
+            return self.is_zero() && other.is_zero();
+        }
+
+        self.monic() == other.monic()
+    }
+
+    /// Divide the polynomial by its leading coefficient.
+    fn monic(&self) -> Polynomial {
+        let leading = self.get(self.degree());
+
+        Polynomial::new(
+            self.coeffs
+                .iter()
+                .map(|(&degree, coeff)| (degree, coeff / &leading))
+                .collect(),
+        )
+    }
+
+    /// Get the content of the polynomial, i.e. the GCD of its numerators over the LCM of its denominators, signed so
+    /// that the primitive part has a positive leading coefficient. The zero polynomial has content 0.
+    pub fn content(&self) -> Rational {
+        self.terms()
+            .fold(Rational::from(0), |gcd, (_, coeff)| gcd.gcd(&coeff))
+            * Rational::from(self.get(self.degree()).signum())
+    }
+
+    /// Divide the polynomial by its content, which leaves integer coefficients without any common factor and doesn't
+    /// change the roots (https://en.wikipedia.org/wiki/Primitive_part_and_content).
+    pub fn primitive_part(&self) -> Polynomial {
+        let content = self.content();
+
+        if content == 0 {
+            return self.clone();
+        }
+
+        Polynomial::new(
+            self.coeffs
+                .iter()
+                .map(|(&degree, coeff)| (degree, coeff / &content))
+                .collect(),
+        )
+    }
+
+    /// Get the reciprocal polynomial `x^n p(1/x)`, which has the coefficients in reverse order. Its roots are the
+    /// reciprocals of the non-zero roots of the original polynomial.
+    ///
+    /// Zero roots turn into a lower degree instead, so reversing twice only gives back the original polynomial if its
+    /// constant term isn't zero.
+    pub fn reverse(&self) -> Polynomial {
+        let degree = self.degree();
+
+        Polynomial::from(
+            self.terms()
+                .map(|(i, coeff)| (degree - i, coeff))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Check whether the coefficients read the same forwards and backwards, i.e. `a_i = a_(n-i)`. The roots of such a
+    /// (self-reciprocal) polynomial come in pairs `r` and `1/r`.
+    pub fn is_palindromic(&self) -> bool {
+        let degree = self.degree();
+
+        (0..=degree / 2).all(|i| self.get(i) == self.get(degree - i))
+    }
+
+    /// Get the sign of the polynomial as `x` goes to infinity, which is the sign of the leading coefficient. The zero
+    /// polynomial has sign 0.
+    pub fn sign_at_infinity(&self) -> i32 {
+        self.get(self.degree()).signum()
+    }
+
+    /// Get the sign of the polynomial as `x` goes to negative infinity, which flips the sign at infinity for odd
+    /// degrees.
+    pub fn sign_at_neg_infinity(&self) -> i32 {
+        if self.degree().is_multiple_of(2) {
+            self.sign_at_infinity()
+        } else {
+            -self.sign_at_infinity()
+        }
+    }
+
+    /// Reduce every coefficient modulo `p` into `0..p`, giving a polynomial over the integers modulo `p`. Fractional
+    /// coefficients are reduced using the modular inverse of their denominator.
+    ///
+    /// Returns `None` if `p` is less than 2, which isn't a useful modulus, or if some denominator isn't invertible modulo
+    /// `p`, i.e. it shares a factor with `p`.
+    pub fn reduce_mod(&self, p: i64) -> Option<Polynomial> {
+        if p < 2 {
+            return None;
+        }
+
+        let p = p as i128;
+        let modulus = BigInt::from(p);
+        // The remainder is below `p`, which is an `i64`, so it always fits
+        let residue = |value: BigInt| (value % modulus.clone()).to_i128().unwrap().rem_euclid(p);
+        let mut reduced = Polynomial::zero();
+
+        for (degree, coeff) in self.terms() {
+            let inverse = mod_inverse(residue(coeff.denom()), p)?;
+
+            reduced.add_term(degree, Rational::from(residue(coeff.numer()) * inverse % p));
+        }
+
+        Some(reduced)
+    }
+
+    /// Find all roots in GF(p), i.e. the residues `0..p` at which the polynomial is 0 modulo `p`, by trying every one
+    /// of them. This is only feasible for small `p`.
+    ///
+    /// If the polynomial is 0 modulo `p` (like `3x + 6` mod 3), every residue is a root.
+    ///
+    /// Panics if `p` is less than 2, or if some coefficient has a denominator which isn't invertible modulo `p`.
+    pub fn roots_mod(&self, p: i64) -> Vec<i64> {
+        assert!(
+            p >= 2,
+            "roots_mod: the modulus must be at least 2, got {}",
+            p
+        );
+
+        let reduced = self
+            .reduce_mod(p)
+            .expect("coefficient denominators must be invertible modulo p");
+
+        if reduced.is_zero() {
+            return (0..p).collect();
+        }
+
+        let p = p as i128;
+
+        (0..p)
+            .filter(|&x| {
+                // Horner's method, reducing after every step so that nothing overflows
+                let value = (0..=reduced.degree()).rev().fold(0, |result, degree| {
+                    (result * x + reduced.get(degree).as_integer().unwrap()) % p
+                });
+
+                value == 0
+            })
+            .map(|x| x as i64)
+            .collect()
+    }
+
     /// Get the first derivative (wrt. `x`) of the polynomial.
     pub fn diff(&self) -> Polynomial {
-        let mut diff_coeffs = HashMap::new();
+        #[cfg(test)]
+        DIFF_CALLS.with(|calls| calls.set(calls.get() + 1));
 
-        for (&degree, &coeff) in &self.coeffs {
+        let mut diff_coeffs = BTreeMap::new();
+
+        for (&degree, coeff) in &self.coeffs {
             // Ignore the 0-th order term as it will be 0
             if degree > 0 {
-                diff_coeffs.insert(degree - 1, coeff * Rational::from(degree));
+                diff_coeffs.insert(degree - 1, coeff * &Rational::from(degree));
             }
         }
 
+        // The derivative of a constant is the zero polynomial
+        if diff_coeffs.is_empty() {
+            diff_coeffs.insert(0, Rational::from(0));
+        }
+
         Polynomial::new(diff_coeffs)
     }
 
-    /// Get the degree of the polynomial.
-    pub fn degree(&self) -> u32 {
-        self.degree
+    /// Get the Taylor shift `p(x + c)` of the polynomial.
+    pub fn shift(&self, c: Rational) -> Polynomial {
+        let x_plus_c = Polynomial::from(vec![(0, c), (1, Rational::from(1))]);
+
+        // Horner's method, but with x + c substituted for x
+        (0..=self.degree())
+            .rev()
+            .fold(Polynomial::zero(), |result, degree| {
+                let mut result = &result * &x_plus_c;
+                result.add_term(0, self.get(degree));
+                result
+            })
     }
-}
 
-impl PartialEq for Polynomial {
-    fn eq(&self, other: &Self) -> bool {
-        self.coeffs == other.coeffs
+    /// Raise the polynomial to the `exponent`-th power, where `p^0` is the constant polynomial `1`.
+    pub fn pow(&self, exponent: u32) -> Polynomial {
+        // Binary exponentiation (https://en.wikipedia.org/wiki/Exponentiation_by_squaring)
+        let mut result = Polynomial::new(BTreeMap::from([(0, Rational::from(1))]));
+        let mut base = self.clone();
+        let mut exponent = exponent;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+
+            exponent >>= 1;
+
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
     }
-}
 
-impl Display for Polynomial {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut exponents = self.coeffs.keys().collect::<Vec<_>>();
+    /// Format the polynomial as LaTeX, with exponents as superscripts and fractional coefficients as `\frac{}{}`, e.g.
+    /// `\frac{3}{4}x^{2} - 1`.
+    pub fn to_latex(&self) -> String {
+        let mut latex = String::new();
 
-        // Sort the exponents in descending order
-        exponents.sort_unstable();
-        exponents.reverse();
+        for (exponent, coeff) in self.terms() {
+            if latex.is_empty() {
+                if coeff.is_negative() {
+                    latex.push('-');
+                }
+            } else if coeff.is_positive() {
+                latex.push_str(" + ");
+            } else {
+                latex.push_str(" - ");
+            }
 
-        for (i, &exponent) in exponents.into_iter().enumerate() {
-            let coeff = self.coeffs[&exponent];
+            if coeff.abs() != 1 || exponent == 0 {
+                latex.push_str(&coeff.abs().to_latex());
+            }
 
-            if coeff == Rational::from(0) {
-                continue;
+            match exponent {
+                0 => (),
+                1 => latex.push('x'),
+                _ => latex.push_str(&format!("x^{{{}}}", exponent)),
             }
+        }
 
-            if i != 0 {
-                if coeff > Rational::from(0) {
-                    write!(f, " + ")?;
-                } else {
-                    write!(f, " - ")?;
+        // Every coefficient is zero
+        if latex.is_empty() {
+            return "0".to_string();
+        }
+
+        latex
+    }
+
+    /// Format the polynomial like [`Display`] does, but with every degree from the leading term down to the constant,
+    /// including the ones whose coefficient is zero, e.g. `x^2 + 0x - 4`. Mostly useful for debugging and teaching.
+    pub fn to_string_dense(&self) -> String {
+        let mut dense = String::new();
+
+        for exponent in (0..=self.degree).rev() {
+            let coeff = self.get(exponent);
+
+            if dense.is_empty() {
+                if coeff.is_negative() {
+                    dense.push('-');
                 }
+            } else if coeff.is_negative() {
+                dense.push_str(" - ");
+            } else {
+                dense.push_str(" + ");
             }
 
-            if coeff != Rational::from(1) {
-                write!(f, "{}", coeff.abs())?;
+            if exponent == 0 {
+                dense.push_str(&coeff.abs().to_string());
+                continue;
             }
 
-            if exponent != 0 {
-                write!(f, "x")?;
+            if coeff.numer().abs() != BigInt::from(1) {
+                dense.push_str(&coeff.numer().abs().to_string());
             }
 
+            dense.push('x');
+
             if exponent > 1 {
-                write!(f, "^{}", exponent)?;
+                dense.push_str(&format!("^{}", exponent));
+            }
+
+            if coeff.denom() != BigInt::from(1) {
+                dense.push_str(&format!("/{}", coeff.denom()));
             }
         }
 
-        Ok(())
+        dense
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Get the degree of the polynomial, i.e. the highest power of `x` with a non-zero coefficient.
+    ///
+    /// The degree of the zero polynomial is mathematically undefined, but it's treated as 0 here just like any other
+    /// constant. Use [`Polynomial::is_zero`] to tell them apart.
+    pub fn degree(&self) -> u32 {
+        self.degree
+    }
 
-    #[test]
-    fn degree() {
-        assert_eq!(
-            Polynomial::new(HashMap::from([
-                (0, Rational::from(1)),
-                (1, Rational::from(2)),
-                (2, Rational::from(3)),
-            ]))
-            .degree(),
-            2
-        );
+    /// Check whether this is the zero polynomial, i.e. every coefficient is zero.
+    pub fn is_zero(&self) -> bool {
+        self.terms().next().is_none()
+    }
 
-        assert_eq!(
-            Polynomial::new(HashMap::from([(4, Rational::from(5)),])).degree(),
-            4
-        );
+    /// Check whether the polynomial is a constant, which includes the zero polynomial.
+    pub fn is_constant(&self) -> bool {
+        self.degree() == 0
     }
 
-    #[test]
-    fn eval() {
-        assert_eq!(
-            Polynomial::new(HashMap::from([
+    /// Get the coefficients `(a, b)` of a linear polynomial `ax + b`, or `None` if the degree isn't exactly 1.
+    pub fn as_linear(&self) -> Option<(Rational, Rational)> {
+        (self.degree() == 1).then(|| (self.get(1), self.get(0)))
+    }
+
+    /// Get the coefficients `(a, b, c)` of a quadratic polynomial `ax^2 + bx + c`, or `None` if the degree isn't exactly
+    /// 2.
+    pub fn as_quadratic(&self) -> Option<(Rational, Rational, Rational)> {
+        (self.degree() == 2).then(|| (self.get(2), self.get(1), self.get(0)))
+    }
+}
+
+impl From<&Polynomial> for Vec<(u32, Rational)> {
+    /// Get the `(degree, coefficient)` pairs of all non-zero terms, in descending order of degree.
+    fn from(poly: &Polynomial) -> Self {
+        poly.terms().collect()
+    }
+}
+
+impl From<Vec<(u32, Rational)>> for Polynomial {
+    /// Build a polynomial from `(degree, coefficient)` pairs, summing the coefficients of repeated degrees.
+    fn from(terms: Vec<(u32, Rational)>) -> Self {
+        let mut poly = Polynomial::zero();
+
+        for (degree, coeff) in terms {
+            poly.add_term(degree, coeff);
+        }
+
+        poly
+    }
+}
+
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = Polynomial::zero();
+
+        for (&lhs_degree, lhs_coeff) in &self.coeffs {
+            for (&rhs_degree, rhs_coeff) in &rhs.coeffs {
+                result.add_term(lhs_degree + rhs_degree, lhs_coeff * rhs_coeff);
+            }
+        }
+
+        result
+    }
+}
+
+impl Mul for Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl AddAssign for Polynomial {
+    fn add_assign(&mut self, rhs: Self) {
+        for (degree, coeff) in rhs.terms() {
+            self.add_term(degree, coeff);
+        }
+    }
+}
+
+impl Add for Polynomial {
+    type Output = Polynomial;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl Sub for Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+/// Add a constant, i.e. adjust the degree-0 term.
+impl AddAssign<Rational> for Polynomial {
+    fn add_assign(&mut self, rhs: Rational) {
+        self.add_term(0, rhs);
+    }
+}
+
+/// Subtract a constant, i.e. adjust the degree-0 term.
+impl SubAssign<Rational> for Polynomial {
+    fn sub_assign(&mut self, rhs: Rational) {
+        self.add_term(0, -rhs);
+    }
+}
+
+/// Scale every coefficient by a constant.
+impl MulAssign<Rational> for Polynomial {
+    fn mul_assign(&mut self, rhs: Rational) {
+        *self = self.map_coeffs(|coeff| coeff * &rhs);
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Self::Output {
+        self.map_coeffs(|coeff| -coeff)
+    }
+}
+
+impl Add<Rational> for Polynomial {
+    type Output = Polynomial;
+
+    fn add(mut self, rhs: Rational) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl Sub<Rational> for Polynomial {
+    type Output = Polynomial;
+
+    fn sub(mut self, rhs: Rational) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl Mul<Rational> for Polynomial {
+    type Output = Polynomial;
+
+    fn mul(mut self, rhs: Rational) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+/// Find the inverse of `a` modulo `m` using the extended Euclidean algorithm, if it exists.
+fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    // (https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm#Modular_integers)
+    let (mut t, mut new_t) = (0, 1);
+    let (mut r, mut new_r) = (m, a.rem_euclid(m));
+
+    while new_r != 0 {
+        let quotient = r / new_r;
+
+        (t, new_t) = (new_t, t - quotient * new_t);
+        (r, new_r) = (new_r, r - quotient * new_r);
+    }
+
+    if r != 1 {
+        return None;
+    }
+
+    Some(t.rem_euclid(m))
+}
+
+impl PartialEq for Polynomial {
+    fn eq(&self, other: &Self) -> bool {
+        // Compare term by term, so that terms with a zero coefficient don't matter
+        (0..=self.degree().max(other.degree())).all(|degree| self.get(degree) == other.get(degree))
+    }
+}
+
+impl Eq for Polynomial {}
+
+/// A polynomial equals a rational if it's the constant polynomial with that value.
+impl PartialEq<Rational> for Polynomial {
+    fn eq(&self, other: &Rational) -> bool {
+        self.is_constant() && self.get(0) == *other
+    }
+}
+
+impl PartialOrd for Polynomial {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Polynomial {
+    /// Order polynomials by degree first, and then lexicographically by their coefficients from the highest degree down.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.degree().cmp(&other.degree()).then_with(|| {
+            (0..=self.degree())
+                .rev()
+                .map(|degree| self.get(degree).cmp(&other.get(degree)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+    }
+}
+
+impl Display for Polynomial {
+    /// Format the polynomial in expanded form, or in factored form (e.g. `(x - 5)^2 (x + 5)`) when the alternate flag
+    /// (`{:#}`) is used and the polynomial can be factored.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            if let Some((leading, factors)) = self.factor() {
+                // Only use the factored form if it's actually different from the expanded one
+                if factors.len() > 1 || factors.iter().any(|&(_, multiplicity)| multiplicity > 1) {
+                    if leading == -1 {
+                        write!(f, "-")?;
+                    } else if leading != 1 {
+                        write!(f, "{}", leading)?;
+                    }
+
+                    for (i, (factor, multiplicity)) in factors.iter().enumerate() {
+                        if i != 0 {
+                            write!(f, " ")?;
+                        }
+
+                        write!(f, "({})", factor)?;
+
+                        if *multiplicity > 1 {
+                            write!(f, "^{}", multiplicity)?;
+                        }
+                    }
+
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut first = true;
+
+        for (exponent, coeff) in self.terms() {
+            if first {
+                if coeff.is_negative() {
+                    write!(f, "-")?;
+                }
+            } else if coeff.is_positive() {
+                write!(f, " + ")?;
+            } else {
+                write!(f, " - ")?;
+            }
+
+            first = false;
+
+            if exponent == 0 {
+                write!(f, "{}", coeff.abs())?;
+                continue;
+            }
+
+            // Fractional coefficients are written as a division after the term (e.g. `3x^2/4`), which is unambiguous and
+            // can be parsed back
+            if coeff.numer().abs() != BigInt::from(1) {
+                write!(f, "{}", coeff.numer().abs())?;
+            }
+
+            write!(f, "x")?;
+
+            if exponent > 1 {
+                write!(f, "^{}", exponent)?;
+            }
+
+            if coeff.denom() != BigInt::from(1) {
+                write!(f, "/{}", coeff.denom())?;
+            }
+        }
+
+        // Every coefficient is zero
+        if first {
+            write!(f, "0")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn low_degree_coefficients() {
+        let quadratic = Polynomial::from(vec![
+            (0, Rational::from(-4)),
+            (1, Rational::new(1, 2)),
+            (2, Rational::from(3)),
+        ]);
+
+        assert_eq!(
+            quadratic.as_quadratic(),
+            Some((Rational::from(3), Rational::new(1, 2), Rational::from(-4)))
+        );
+        assert_eq!(quadratic.as_linear(), None);
+        assert_eq!(
+            quadratic.diff().as_linear(),
+            Some((Rational::from(6), Rational::new(1, 2)))
+        );
+
+        let cubic = Polynomial::from(vec![(0, Rational::from(1)), (3, Rational::from(1))]);
+
+        assert_eq!(cubic.as_quadratic(), None);
+        assert_eq!(cubic.as_linear(), None);
+        assert_eq!(Polynomial::zero().as_linear(), None);
+    }
+
+    #[test]
+    fn degree() {
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(1)),
+                (1, Rational::from(2)),
+                (2, Rational::from(3)),
+            ]))
+            .degree(),
+            2
+        );
+
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([(4, Rational::from(5)),])).degree(),
+            4
+        );
+
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
+                (1, Rational::from(1)),
+                (3, Rational::from(0)),
+            ]))
+            .degree(),
+            1
+        );
+    }
+
+    #[test]
+    fn constants() {
+        let five = Polynomial::from(vec![(0, Rational::from(5))]);
+
+        assert_eq!(five.degree(), 0);
+        assert!(five.is_constant());
+        assert!(!five.is_zero());
+
+        for zero in [
+            Polynomial::zero(),
+            Polynomial::new(BTreeMap::from([(0, Rational::from(0))])),
+            Polynomial::new(BTreeMap::from([(2, Rational::from(0))])),
+        ] {
+            assert_eq!(zero.degree(), 0);
+            assert!(zero.is_constant());
+            assert!(zero.is_zero());
+        }
+
+        let x = Polynomial::from(vec![(1, Rational::from(1))]);
+
+        assert!(!x.is_constant());
+        assert!(!x.is_zero());
+    }
+
+    #[test]
+    fn term_pairs() {
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-3)),
+            (1, Rational::from(0)),
+            (4, Rational::new(1, 2)),
+        ]);
+        let terms = Vec::from(&poly);
+
+        assert_eq!(
+            terms,
+            vec![(4, Rational::new(1, 2)), (0, Rational::from(-3))]
+        );
+        assert_eq!(Polynomial::from(terms), poly);
+        assert!(Vec::from(&Polynomial::zero()).is_empty());
+    }
+
+    #[test]
+    fn map_coeffs() {
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-3)),
+            (1, Rational::new(1, 2)),
+            (4, Rational::from(2)),
+        ]);
+
+        assert_eq!(
+            poly.map_coeffs(|coeff| coeff * Rational::from(2)),
+            poly.clone() * Rational::from(2)
+        );
+        assert_eq!(
+            poly.map_coeffs(|coeff| -coeff),
+            Polynomial::from(vec![
+                (0, Rational::from(3)),
+                (1, Rational::new(-1, 2)),
+                (4, Rational::from(-2)),
+            ])
+        );
+        assert_eq!(-poly.clone(), poly.map_coeffs(|coeff| -coeff));
+
+        // Coefficients mapped to zero are dropped, which can lower the degree
+        let truncated = poly.map_coeffs(|coeff| {
+            if coeff.as_integer().is_some() {
+                Rational::from(0)
+            } else {
+                coeff
+            }
+        });
+        assert_eq!(truncated.degree(), 1);
+        assert_eq!(truncated.terms().count(), 1);
+        assert!(poly.map_coeffs(|_| Rational::from(0)).is_zero());
+    }
+
+    #[test]
+    fn add_term() {
+        let mut poly = Polynomial::zero();
+
+        poly.add_term(1, Rational::from(3));
+        assert_eq!(poly.degree(), 1);
+
+        poly.add_term(3, Rational::from(2));
+        poly.add_term(1, Rational::from(-1));
+        assert_eq!(poly.degree(), 3);
+        assert_eq!(
+            poly,
+            Polynomial::new(BTreeMap::from([
+                (1, Rational::from(2)),
+                (3, Rational::from(2)),
+            ]))
+        );
+
+        // Cancelling out the leading term lowers the degree
+        poly.add_term(3, Rational::from(-2));
+        assert_eq!(poly.degree(), 1);
+        assert_eq!(
+            poly,
+            Polynomial::new(BTreeMap::from([(1, Rational::from(2))]))
+        );
+
+        poly.add_term(1, Rational::from(-2));
+        assert_eq!(poly.degree(), 0);
+        assert_eq!(poly, Polynomial::zero());
+    }
+
+    #[test]
+    fn from_vec() {
+        assert_eq!(
+            Polynomial::from(vec![(2, 1.into()), (2, 2.into())]),
+            Polynomial::new(BTreeMap::from([(2, Rational::from(3))]))
+        );
+
+        let poly = Polynomial::from(vec![(0, 5.into()), (1, 1.into()), (1, (-1).into())]);
+
+        assert_eq!(poly.degree(), 0);
+        assert_eq!(poly.get(0), Rational::from(5));
+        assert_eq!(Polynomial::from(vec![]), Polynomial::zero());
+    }
+
+    #[test]
+    fn coefficients_are_ordered() {
+        let poly = Polynomial::from(vec![
+            (5, Rational::from(1)),
+            (0, Rational::from(2)),
+            (3, Rational::from(3)),
+            (1, Rational::from(4)),
+        ]);
+
+        assert_eq!(
+            poly.coeffs.keys().copied().collect::<Vec<_>>(),
+            vec![0, 1, 3, 5]
+        );
+    }
+
+    #[test]
+    fn terms() {
+        let poly = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(-4)),
+            (2, Rational::from(0)),
+            (5, Rational::new(1, 2)),
+            (3, Rational::from(7)),
+        ]));
+
+        assert_eq!(
+            poly.terms().collect::<Vec<_>>(),
+            vec![
+                (5, Rational::new(1, 2)),
+                (3, Rational::from(7)),
+                (0, Rational::from(-4)),
+            ]
+        );
+
+        assert_eq!(Polynomial::zero().terms().count(), 0);
+    }
+
+    #[test]
+    fn eval() {
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
                 (0, Rational::from(1)),
                 (1, Rational::from(2)),
                 (2, Rational::from(3)),
@@ -139,7 +1307,7 @@ mod tests {
         );
 
         assert_eq!(
-            Polynomial::new(HashMap::from([
+            Polynomial::new(BTreeMap::from([
                 (0, Rational::from(1)),
                 (1, Rational::from(2)),
                 (4, Rational::from(5)),
@@ -149,29 +1317,1118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn latex() {
+        assert_eq!(
+            parser::parse_polynomial_expr("3x^2/4 - x + 1/2")
+                .unwrap()
+                .to_latex(),
+            "\\frac{3}{4}x^{2} - x + \\frac{1}{2}"
+        );
+        assert_eq!(
+            parser::parse_polynomial_expr("-x^10 + 2x")
+                .unwrap()
+                .to_latex(),
+            "-x^{10} + 2x"
+        );
+        assert_eq!(Polynomial::zero().to_latex(), "0");
+    }
+
+    #[test]
+    fn eval_all() {
+        // 2x^3 - x/2 + 1
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(1)),
+            (1, Rational::new(-1, 2)),
+            (3, Rational::from(2)),
+        ]);
+
+        let xs = (-4..=4).map(|n| Rational::new(n, 2)).collect::<Vec<_>>();
+
+        assert_eq!(
+            poly.eval_all(&xs),
+            xs.iter().map(|x| poly.eval(x.clone())).collect::<Vec<_>>()
+        );
+
+        let xs = xs.iter().map(|x| x.to_f64()).collect::<Vec<_>>();
+
+        for (value, &x) in poly.eval_all_f64(&xs).into_iter().zip(&xs) {
+            assert!((value - poly.eval_f64(x)).abs() < 1e-12);
+        }
+
+        assert!(poly.eval_all(&[]).is_empty());
+        assert_eq!(
+            Polynomial::zero().eval_all(&[Rational::from(3)]),
+            vec![Rational::from(0)]
+        );
+    }
+
+    #[test]
+    fn eval_interval() {
+        // x^2 - 2 changes sign between 1 and 2, at sqrt(2)
+        let poly = Polynomial::from(vec![(0, Rational::from(-2)), (2, Rational::from(1))]);
+        let bounds =
+            poly.eval_interval(RationalInterval::new(Rational::from(1), Rational::from(2)));
+
+        assert!(bounds.contains(Rational::from(0)));
+        assert!(bounds.contains(poly.eval(Rational::new(3, 2))));
+        assert_eq!(bounds.signum(), None);
+
+        // The signs at the endpoints are exact, which confirms the sign change
+        assert_eq!(
+            poly.eval_interval(RationalInterval::point(Rational::from(1)))
+                .signum(),
+            Some(-1)
+        );
+        assert_eq!(
+            poly.eval_interval(RationalInterval::point(Rational::from(2)))
+                .signum(),
+            Some(1)
+        );
+
+        // There's no root in [2, 3]
+        assert_eq!(
+            poly.eval_interval(RationalInterval::new(Rational::from(2), Rational::from(3)))
+                .signum(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn eval_bigint() {
+        let big = BigInt::from(10i128.pow(30));
+
+        // x^2 - 10^30 x has the root 10^30, even though (10^30)^2 doesn't fit into an i128
+        let poly = Polynomial::from(vec![
+            (1, Rational::from(-(10i128.pow(30)))),
+            (2, Rational::from(1)),
+        ]);
+        assert_eq!(poly.eval_bigint(&big), Rational::from(0));
+        assert_eq!(
+            poly.eval_bigint(&(big.clone() + BigInt::from(1))),
+            Rational::from(10i128.pow(30) + 1)
+        );
+
+        // x^2/2 - 3x/4 + 1 at 2^40
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(1)),
+            (1, Rational::new(-3, 4)),
+            (2, Rational::new(1, 2)),
+        ]);
+        let x = 1i128 << 40;
+        assert_eq!(
+            poly.eval_bigint(&BigInt::from(x)),
+            poly.eval(Rational::from(x))
+        );
+        assert_eq!(
+            poly.eval_bigint(&BigInt::from(x)),
+            Rational::new(x * x * 2 - 3 * x + 4, 4)
+        );
+
+        // (10^30)^3 doesn't fit into an i128 either
+        assert_eq!(
+            Polynomial::from(vec![(3, Rational::from(1))]).eval_bigint(&big),
+            Rational::from(big.pow(3))
+        );
+
+        // The denominators are distinct Mersenne primes, so their LCM doesn't fit into an i128
+        let poly = Polynomial::from(vec![
+            (2, Rational::new(1, (1 << 61) - 1)),
+            (1, Rational::new(1, (1 << 89) - 1)),
+            (0, Rational::new(1, (1 << 107) - 1)),
+        ]);
+        assert_eq!(
+            poly.eval_bigint(&BigInt::zero()),
+            Rational::new(1, (1 << 107) - 1)
+        );
+        assert_eq!(
+            poly.eval_bigint(&BigInt::from(1)),
+            Rational::new(1, (1 << 61) - 1)
+                + Rational::new(1, (1 << 89) - 1)
+                + Rational::new(1, (1 << 107) - 1)
+        );
+    }
+
+    #[test]
+    fn eval_with_derivative() {
+        let poly = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(-4)),
+            (1, Rational::new(1, 2)),
+            (2, Rational::from(-2)),
+            (3, Rational::from(1)),
+        ]));
+
+        for x in [Rational::from(0), Rational::from(3), Rational::new(-5, 3)] {
+            assert_eq!(
+                poly.eval_with_derivative(x.clone()),
+                (poly.eval(x.clone()), poly.diff().eval(x))
+            );
+        }
+
+        assert_eq!(
+            Polynomial::zero().eval_with_derivative(Rational::from(2)),
+            (Rational::from(0), Rational::from(0))
+        );
+    }
+
+    #[test]
+    fn div_rem() {
+        // (x^3 - 2x^2 - 4) / (x - 3) = x^2 + x + 3, remainder 5
+        let (quotient, remainder) = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(-4)),
+            (2, Rational::from(-2)),
+            (3, Rational::from(1)),
+        ]))
+        .div_rem(&Polynomial::new(BTreeMap::from([
+            (0, Rational::from(-3)),
+            (1, Rational::from(1)),
+        ])));
+
+        assert_eq!(
+            quotient,
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(3)),
+                (1, Rational::from(1)),
+                (2, Rational::from(1)),
+            ]))
+        );
+        assert_eq!(
+            remainder,
+            Polynomial::new(BTreeMap::from([(0, Rational::from(5))]))
+        );
+    }
+
+    #[test]
+    fn quotient_and_remainder() {
+        // (x^4 + 3x - 1/2) / (2x^2 - x + 1)
+        let poly = Polynomial::from(vec![
+            (0, Rational::new(-1, 2)),
+            (1, Rational::from(3)),
+            (4, Rational::from(1)),
+        ]);
+        let divisor = Polynomial::from(vec![
+            (0, Rational::from(1)),
+            (1, Rational::from(-1)),
+            (2, Rational::from(2)),
+        ]);
+
+        let (quotient, remainder) = poly.div_rem(&divisor);
+
+        assert_eq!(poly.quotient(&divisor), quotient);
+        assert_eq!(poly.remainder(&divisor), remainder);
+        assert!(remainder.degree() < divisor.degree());
+        assert_eq!(divisor * quotient + remainder, poly);
+    }
+
+    #[test]
+    fn equal_up_to_scalar() {
+        let parse = |input| crate::parser::parse_polynomial_expr(input).unwrap();
+
+        assert!(parse("2x^2 - 2").equal_up_to_scalar(&parse("x^2 - 1")));
+        assert!(parse("x^2 - 1").equal_up_to_scalar(&parse("-x^2/3 + 1/3")));
+        assert!(!parse("x^2 - 1").equal_up_to_scalar(&parse("x^2 + 1")));
+        assert!(!parse("x^2 - 1").equal_up_to_scalar(&parse("x^3 - x")));
+
+        assert!(parse("5").equal_up_to_scalar(&parse("-1/2")));
+        assert!(!parse("5").equal_up_to_scalar(&Polynomial::zero()));
+        assert!(Polynomial::zero().equal_up_to_scalar(&Polynomial::zero()));
+    }
+
+    #[test]
+    fn divides() {
+        let factor = Polynomial::from(vec![(0, Rational::from(-1)), (1, Rational::from(1))]);
+
+        // x^2 - 1 = (x - 1)(x + 1), but x^2 + 1 has no real roots at all
+        assert!(factor.divides(&Polynomial::from(vec![
+            (0, Rational::from(-1)),
+            (2, Rational::from(1)),
+        ])));
+        assert!(!factor.divides(&Polynomial::from(vec![
+            (0, Rational::from(1)),
+            (2, Rational::from(1)),
+        ])));
+
+        assert!(factor.divides(&Polynomial::zero()));
+        assert!(Polynomial::from(vec![(0, Rational::new(1, 2))]).divides(&factor));
+        assert!(!factor.divides(&Polynomial::from(vec![(0, Rational::from(3))])));
+        assert!(!Polynomial::zero().divides(&factor));
+        assert!(Polynomial::zero().divides(&Polynomial::zero()));
+    }
+
+    #[test]
+    fn synthetic_div() {
+        // (x^2 - 1) / (x - 1) = x + 1
+        let (quotient, remainder) =
+            Polynomial::from(vec![(0, Rational::from(-1)), (2, Rational::from(1))])
+                .synthetic_div(Rational::from(1));
+
+        assert_eq!(
+            quotient,
+            Polynomial::from(vec![(0, Rational::from(1)), (1, Rational::from(1))])
+        );
+        assert_eq!(remainder, Rational::from(0));
+
+        // Matches the general long division
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-4)),
+            (2, Rational::from(-2)),
+            (3, Rational::new(1, 2)),
+        ]);
+        let root = Rational::new(-2, 3);
+        let (quotient, remainder) = poly.synthetic_div(root.clone());
+
+        assert_eq!(
+            (quotient, Polynomial::from(vec![(0, remainder.clone())])),
+            poly.div_rem(&Polynomial::from(vec![(0, -&root), (1, Rational::from(1))]))
+        );
+        assert_eq!(remainder, poly.eval(root));
+
+        // A constant is all remainder
+        assert_eq!(
+            Polynomial::from(vec![(0, Rational::from(5))]).synthetic_div(Rational::from(2)),
+            (Polynomial::zero(), Rational::from(5))
+        );
+    }
+
+    #[test]
+    fn roots_f64() {
+        // (x - 1)(x^2 - 2) = x^3 - x^2 - 2x + 2
+        let roots = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(2)),
+            (1, Rational::from(-2)),
+            (2, Rational::from(-1)),
+            (3, Rational::from(1)),
+        ]))
+        .roots_f64();
+
+        let expected = [-core::f64::consts::SQRT_2, 1.0, core::f64::consts::SQRT_2];
+
+        assert_eq!(roots.len(), expected.len());
+        for (root, expected) in roots.into_iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-9, "{} != {}", root, expected);
+        }
+
+        // x^3 - 3x + 1 has three irrational roots: 2cos(2pi/9), 2cos(4pi/9) and 2cos(8pi/9)
+        let roots = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(1)),
+            (1, Rational::from(-3)),
+            (3, Rational::from(1)),
+        ]))
+        .roots_f64();
+
+        let expected = [-1.879385241571817, 0.3472963553338607, 1.532088886237956];
+
+        assert_eq!(roots.len(), expected.len());
+        for (root, expected) in roots.into_iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-9, "{} != {}", root, expected);
+        }
+    }
+
+    #[test]
+    fn ord() {
+        let x_plus_5 = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(5)),
+            (1, Rational::from(1)),
+        ]));
+        let x_minus_5 = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(-5)),
+            (1, Rational::from(1)),
+        ]));
+        let two_x = Polynomial::new(BTreeMap::from([(1, Rational::from(2))]));
+        let x_squared = Polynomial::new(BTreeMap::from([(2, Rational::from(1))]));
+        let seven = Polynomial::new(BTreeMap::from([(0, Rational::from(7))]));
+
+        let mut polys = vec![
+            x_squared.clone(),
+            two_x.clone(),
+            x_plus_5.clone(),
+            seven.clone(),
+            x_minus_5.clone(),
+        ];
+        polys.sort();
+
+        assert_eq!(polys, vec![seven, x_minus_5, x_plus_5, two_x, x_squared]);
+
+        // Zero coefficients don't affect the order, just like equality
+        assert_eq!(
+            Polynomial::zero().cmp(&Polynomial::new(BTreeMap::from([(0, Rational::from(0))]))),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(25)),
+                (1, Rational::from(-10)),
+                (2, Rational::from(1)),
+            ]))
+            .to_string(),
+            "x^2 - 10x + 25"
+        );
+
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(1)),
+                (1, Rational::from(-1)),
+                (3, Rational::from(-2)),
+            ]))
+            .to_string(),
+            "-2x^3 - x + 1"
+        );
+
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::new(-5, 6)),
+                (1, Rational::new(-1, 3)),
+                (2, Rational::new(3, 4)),
+            ]))
+            .to_string(),
+            "3x^2/4 - x/3 - 5/6"
+        );
+    }
+
+    #[test]
+    fn dense_display() {
+        let poly = Polynomial::from(vec![(2, Rational::from(1)), (0, Rational::from(-4))]);
+
+        assert_eq!(poly.to_string_dense(), "x^2 + 0x - 4");
+        assert_eq!(
+            Polynomial::from(vec![(3, Rational::new(-1, 2)), (1, Rational::from(2))])
+                .to_string_dense(),
+            "-x^3/2 + 0x^2 + 2x + 0"
+        );
+        assert_eq!(Polynomial::zero().to_string_dense(), "0");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for poly in [
+            Polynomial::from(vec![
+                (0, Rational::new(-5, 6)),
+                (1, Rational::new(-1, 3)),
+                (2, Rational::new(3, 4)),
+            ]),
+            Polynomial::from(vec![(3, Rational::new(-1, 2)), (1, Rational::new(7, 5))]),
+            Polynomial::from(vec![(0, Rational::new(1, 2))]),
+        ] {
+            assert_eq!(
+                parser::parse_polynomial_expr(&poly.to_string()).unwrap(),
+                poly
+            );
+        }
+    }
+
+    #[test]
+    fn display_factored() {
+        assert_eq!(
+            format!(
+                "{:#}",
+                Polynomial::new(BTreeMap::from([
+                    (0, Rational::from(25)),
+                    (1, Rational::from(-10)),
+                    (2, Rational::from(1)),
+                ]))
+            ),
+            "(x - 5)^2"
+        );
+
+        assert_eq!(
+            format!(
+                "{:#}",
+                Polynomial::new(BTreeMap::from([
+                    (0, Rational::from(-125)),
+                    (1, Rational::from(-25)),
+                    (2, Rational::from(5)),
+                    (3, Rational::from(1)),
+                ]))
+            ),
+            "(x + 5)^2 (x - 5)"
+        );
+
+        // 2(x - 1)(x^2 + 1) = 2x^3 - 2x^2 + 2x - 2
+        assert_eq!(
+            format!(
+                "{:#}",
+                Polynomial::new(BTreeMap::from([
+                    (0, Rational::from(-2)),
+                    (1, Rational::from(2)),
+                    (2, Rational::from(-2)),
+                    (3, Rational::from(2)),
+                ]))
+            ),
+            "2(x - 1) (x^2 + 1)"
+        );
+
+        // Irreducible polynomials fall back to the expanded form
+        assert_eq!(
+            format!(
+                "{:#}",
+                Polynomial::new(BTreeMap::from([
+                    (0, Rational::from(1)),
+                    (2, Rational::from(1)),
+                ]))
+            ),
+            "x^2 + 1"
+        );
+    }
+
+    #[test]
+    fn gcd() {
+        // (x - 1)(x - 2) and (x - 2)(x - 3) share the factor x - 2
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(2)),
+                (1, Rational::from(-3)),
+                (2, Rational::from(1)),
+            ]))
+            .gcd(&Polynomial::new(BTreeMap::from([
+                (0, Rational::from(12)),
+                (1, Rational::from(-10)),
+                (2, Rational::from(2)),
+            ]))),
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(-2)),
+                (1, Rational::from(1)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn resultant() {
+        let from_roots = |roots: &[i32]| {
+            roots.iter().fold(
+                Polynomial::from(vec![(0, Rational::from(1))]),
+                |poly, &root| {
+                    &poly
+                        * &Polynomial::from(vec![
+                            (0, Rational::from(-root)),
+                            (1, Rational::from(1)),
+                        ])
+                },
+            )
+        };
+
+        // (x - 1)(x - 2) and (x - 2)(x - 3) share the root 2
+        assert_eq!(
+            from_roots(&[1, 2]).resultant(&from_roots(&[2, 3])),
+            Rational::from(0)
+        );
+
+        // For monic polynomials, the resultant is the product of all differences of their roots
+        assert_eq!(
+            from_roots(&[1]).resultant(&from_roots(&[2])),
+            Rational::from(-1)
+        );
+        assert_eq!(
+            from_roots(&[1, 2]).resultant(&from_roots(&[3, 5])),
+            Rational::from((1 - 3) * (1 - 5) * (2 - 3) * (2 - 5))
+        );
+        assert_eq!(
+            from_roots(&[1, -1]).resultant(&from_roots(&[2])),
+            Rational::from(3)
+        );
+
+        // x^2 + 1 and x^2 - 1 are coprime, with res = (i^2 - 1)((-i)^2 - 1) = 4
+        let x_squared_plus_1 =
+            Polynomial::from(vec![(0, Rational::from(1)), (2, Rational::from(1))]);
+        assert_eq!(
+            x_squared_plus_1.resultant(&from_roots(&[1, -1])),
+            Rational::from(4)
+        );
+
+        // res(a, b) = (-1)^(deg a * deg b) res(b, a)
+        assert_eq!(
+            from_roots(&[1, 2]).resultant(&from_roots(&[3, 5, 7])),
+            from_roots(&[3, 5, 7]).resultant(&from_roots(&[1, 2]))
+        );
+        assert_eq!(
+            from_roots(&[1]).resultant(&from_roots(&[3, 5, 7])),
+            -from_roots(&[3, 5, 7]).resultant(&from_roots(&[1]))
+        );
+
+        // Constants and non-monic polynomials
+        let constant = Polynomial::from(vec![(0, Rational::from(3))]);
+        assert_eq!(constant.resultant(&from_roots(&[1, 2])), Rational::from(9));
+        assert_eq!(
+            Polynomial::from(vec![(0, Rational::from(-1)), (1, Rational::from(2))])
+                .resultant(&x_squared_plus_1),
+            Rational::from(5)
+        );
+        assert_eq!(Polynomial::zero().resultant(&constant), Rational::from(0));
+    }
+
+    #[test]
+    fn discriminant() {
+        for (a, b, c) in [(1, -5, 6), (2, 3, 7), (-3, 0, 12), (1, -6, 9), (5, 1, 0)] {
+            let (a, b, c) = (Rational::from(a), Rational::from(b), Rational::from(c));
+            let poly = Polynomial::from(vec![(0, c.clone()), (1, b.clone()), (2, a.clone())]);
+
+            assert_eq!(poly.discriminant(), &b * &b - Rational::from(4) * a * c);
+        }
+
+        for (a, b, c, d) in [
+            (1, 5, -25, -125),
+            (1, -1, 1, -1),
+            (2, -3, 0, 7),
+            (-1, 4, 2, -9),
+            (3, 0, 0, 0),
+        ] {
+            let (a, b, c, d) = (
+                Rational::from(a),
+                Rational::from(b),
+                Rational::from(c),
+                Rational::from(d),
+            );
+            let poly = Polynomial::from(vec![
+                (0, d.clone()),
+                (1, c.clone()),
+                (2, b.clone()),
+                (3, a.clone()),
+            ]);
+
+            assert_eq!(
+                poly.discriminant(),
+                &b * &b * &c * &c
+                    - Rational::from(4) * &a * &c * &c * &c
+                    - Rational::from(4) * &b * &b * &b * &d
+                    - Rational::from(27) * &a * &a * &d * &d
+                    + Rational::from(18) * &a * &b * &c * &d
+            );
+        }
+
+        // (x + 5)^2 (x - 5) has a repeated root
+        assert_eq!(
+            Polynomial::from(vec![
+                (0, Rational::from(-125)),
+                (1, Rational::from(-25)),
+                (2, Rational::from(5)),
+                (3, Rational::from(1)),
+            ])
+            .discriminant(),
+            Rational::from(0)
+        );
+
+        assert_eq!(
+            Polynomial::from(vec![(0, Rational::from(-2)), (1, Rational::from(3))]).discriminant(),
+            Rational::from(1)
+        );
+        assert_eq!(
+            Polynomial::from(vec![(0, Rational::from(7))]).discriminant(),
+            Rational::from(1)
+        );
+        assert_eq!(Polynomial::zero().discriminant(), Rational::from(0));
+    }
+
+    #[test]
+    fn is_squarefree() {
+        // (x - 1)(x - 2)
+        assert!(Polynomial::new(BTreeMap::from([
+            (0, Rational::from(2)),
+            (1, Rational::from(-3)),
+            (2, Rational::from(1)),
+        ]))
+        .is_squarefree());
+
+        // (x - 1)^2
+        assert!(!Polynomial::new(BTreeMap::from([
+            (0, Rational::from(1)),
+            (1, Rational::from(-2)),
+            (2, Rational::from(1)),
+        ]))
+        .is_squarefree());
+
+        assert!(Polynomial::new(BTreeMap::from([(0, Rational::from(5))])).is_squarefree());
+    }
+
+    #[test]
+    fn squarefree_decomposition() {
+        let x_minus =
+            |root: i32| Polynomial::from(vec![(0, Rational::from(-root)), (1, Rational::from(1))]);
+        let x2_minus_2 = Polynomial::from(vec![(0, Rational::from(-2)), (2, Rational::from(1))]);
+
+        // 3 (x - 1) (x^2 - 2)^2 (x + 4)^4
+        let poly = Polynomial::from(vec![(0, Rational::from(3))])
+            * x_minus(1)
+            * x2_minus_2.pow(2)
+            * x_minus(-4).pow(4);
+
+        assert_eq!(
+            poly.squarefree_decomposition(),
+            vec![(x_minus(1), 1), (x2_minus_2, 2), (x_minus(-4), 4)]
+        );
+
+        // Square-free polynomials are their own (monic) decomposition
+        let poly = Polynomial::from(vec![(0, Rational::from(-4)), (2, Rational::from(2))]);
+        assert_eq!(poly.squarefree_decomposition(), vec![(poly.monic(), 1)]);
+
+        assert_eq!(
+            Polynomial::from(vec![(0, Rational::from(5))]).squarefree_decomposition(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn multiplicity_at() {
+        // (x + 3)^3
+        let poly = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(27)),
+            (1, Rational::from(27)),
+            (2, Rational::from(9)),
+            (3, Rational::from(1)),
+        ]));
+
+        assert_eq!(poly.multiplicity_at(Rational::from(-3)), 3);
+        assert_eq!(poly.multiplicity_at(Rational::from(3)), 0);
+
+        // (x - 1)^2 (x + 1/2)
+        let poly = Polynomial::new(BTreeMap::from([
+            (0, Rational::new(1, 2)),
+            (1, Rational::from(0)),
+            (2, Rational::new(-3, 2)),
+            (3, Rational::from(1)),
+        ]));
+
+        assert_eq!(poly.multiplicity_at(Rational::from(1)), 2);
+        assert_eq!(poly.multiplicity_at(Rational::new(-1, 2)), 1);
+    }
+
+    #[test]
+    fn mul() {
+        // (x + 1)(x^2 - x + 1) = x^3 + 1
+        assert_eq!(
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(1)),
+                (1, Rational::from(1)),
+            ])) * Polynomial::new(BTreeMap::from([
+                (0, Rational::from(1)),
+                (1, Rational::from(-1)),
+                (2, Rational::from(1)),
+            ])),
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(1)),
+                (3, Rational::from(1)),
+            ]))
+        );
+
+        assert_eq!(
+            &Polynomial::zero() * &Polynomial::new(BTreeMap::from([(2, Rational::from(3))])),
+            Polynomial::zero()
+        );
+    }
+
+    #[test]
+    fn shift() {
+        // (x + 1)^2 = x^2 + 2x + 1
+        assert_eq!(
+            Polynomial::from(vec![(2, Rational::from(1))]).shift(Rational::from(1)),
+            Polynomial::from(vec![
+                (0, Rational::from(1)),
+                (1, Rational::from(2)),
+                (2, Rational::from(1)),
+            ])
+        );
+
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-4)),
+            (1, Rational::new(1, 2)),
+            (3, Rational::from(7)),
+        ]);
+
+        assert_eq!(poly.shift(Rational::from(0)), poly);
+
+        let shifted = poly.shift(Rational::new(-2, 3));
+        for x in [Rational::from(0), Rational::from(5), Rational::new(-1, 7)] {
+            assert_eq!(shifted.eval(x.clone()), poly.eval(x + Rational::new(-2, 3)));
+        }
+
+        assert_eq!(
+            Polynomial::zero().shift(Rational::from(3)),
+            Polynomial::zero()
+        );
+    }
+
+    #[test]
+    fn pow() {
+        let poly = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(-4)),
+            (1, Rational::from(1)),
+        ]));
+
+        // (x - 4)^4 = x^4 - 16x^3 + 96x^2 - 256x + 256
+        assert_eq!(
+            poly.pow(4),
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(256)),
+                (1, Rational::from(-256)),
+                (2, Rational::from(96)),
+                (3, Rational::from(-16)),
+                (4, Rational::from(1)),
+            ]))
+        );
+        assert_eq!(poly.pow(4).degree(), 4);
+
+        assert_eq!(
+            poly.pow(0),
+            Polynomial::new(BTreeMap::from([(0, Rational::from(1))]))
+        );
+        assert_eq!(poly.pow(1), poly);
+    }
+
+    #[test]
+    fn content_and_primitive_part() {
+        // 2x^2 + 4x + 6 = 2 (x^2 + 2x + 3)
+        let poly = Polynomial::new(BTreeMap::from([
+            (0, Rational::from(6)),
+            (1, Rational::from(4)),
+            (2, Rational::from(2)),
+        ]));
+
+        assert_eq!(poly.content(), Rational::from(2));
+        assert_eq!(
+            poly.primitive_part(),
+            Polynomial::new(BTreeMap::from([
+                (0, Rational::from(3)),
+                (1, Rational::from(2)),
+                (2, Rational::from(1)),
+            ]))
+        );
+
+        // -x^2/2 + 3x/4 = -1/4 (2x^2 - 3x)
+        let poly = Polynomial::new(BTreeMap::from([
+            (1, Rational::new(3, 4)),
+            (2, Rational::new(-1, 2)),
+        ]));
+
+        assert_eq!(poly.content(), Rational::new(-1, 4));
+        assert_eq!(
+            poly.primitive_part(),
+            Polynomial::new(BTreeMap::from([
+                (1, Rational::from(-3)),
+                (2, Rational::from(2)),
+            ]))
+        );
+
+        assert_eq!(Polynomial::zero().content(), Rational::from(0));
+        assert_eq!(Polynomial::zero().primitive_part(), Polynomial::zero());
+    }
+
+    #[test]
+    fn scalar_ops() {
+        // x^2 - 3x
+        let poly = Polynomial::from(vec![(1, Rational::from(-3)), (2, Rational::from(1))]);
+        let constant = |value| Polynomial::from(vec![(0, Rational::from(value))]);
+
+        assert_eq!(
+            poly.clone() + Rational::from(3),
+            Polynomial::from(vec![
+                (0, Rational::from(3)),
+                (1, Rational::from(-3)),
+                (2, Rational::from(1)),
+            ])
+        );
+        assert_eq!(
+            poly.clone() * Rational::from(2),
+            Polynomial::from(vec![(1, Rational::from(-6)), (2, Rational::from(2))])
+        );
+
+        // Matches going through a constant polynomial
+        assert_eq!(poly.clone() * Rational::from(2), &poly * &constant(2));
+        assert_eq!(poly.clone() + Rational::from(3) - Rational::from(3), poly);
+
+        assert_eq!(constant(4), Rational::from(4));
+        assert_eq!(Polynomial::zero(), Rational::from(0));
+        assert_ne!(poly + Rational::from(4), Rational::from(4));
+    }
+
+    #[test]
+    fn scalar_assign_ops() {
+        // x^2 + 3
+        let mut poly = Polynomial::from(vec![(0, Rational::from(3)), (2, Rational::from(1))]);
+
+        poly += Rational::new(1, 2);
+        assert_eq!(
+            poly,
+            Polynomial::from(vec![(0, Rational::new(7, 2)), (2, Rational::from(1))])
+        );
+
+        // The constant term cancels out completely
+        poly -= Rational::new(7, 2);
+        assert_eq!(poly, Polynomial::from(vec![(2, Rational::from(1))]));
+        assert_eq!(poly.terms().count(), 1);
+
+        poly *= Rational::from(-2);
+        assert_eq!(poly, Polynomial::from(vec![(2, Rational::from(-2))]));
+        assert_eq!(poly.degree(), 2);
+
+        poly *= Rational::from(0);
+        assert!(poly.is_zero());
+        assert_eq!(poly.degree(), 0);
+
+        // Adding a constant to the zero polynomial
+        poly += Rational::from(5);
+        assert_eq!(poly, Polynomial::from(vec![(0, Rational::from(5))]));
+    }
+
+    #[test]
+    fn reverse() {
+        let parse = |input| crate::parser::parse_polynomial_expr(input).unwrap();
+
+        assert_eq!(parse("2x^2 + 3x + 1").reverse(), parse("x^2 + 3x + 2"));
+        assert_eq!(
+            parse("2x^2 + 3x + 1").reverse().reverse(),
+            parse("2x^2 + 3x + 1")
+        );
+
+        // The roots -1/2 and -1 become -2 and -1
+        let reversed = parse("2x^2 + 3x + 1").reverse();
+        assert_eq!(reversed.eval(Rational::from(-2)), Rational::from(0));
+        assert_eq!(reversed.eval(Rational::from(-1)), Rational::from(0));
+
+        // Palindromic polynomials are their own reverse
+        assert_eq!(
+            parse("x^4 - 3x^3 + 5x^2 - 3x + 1").reverse(),
+            parse("x^4 - 3x^3 + 5x^2 - 3x + 1")
+        );
+
+        // A zero root lowers the degree
+        assert_eq!(parse("x^3 - 2x").reverse(), parse("-2x^2 + 1"));
+        assert_eq!(parse("x^3 - 2x").reverse().reverse(), parse("x^2 - 2"));
+        assert!(Polynomial::zero().reverse().is_zero());
+    }
+
+    #[test]
+    fn is_palindromic() {
+        let palindromic = |input| {
+            parser::parse_polynomial_expr(input)
+                .unwrap()
+                .is_palindromic()
+        };
+
+        assert!(palindromic("x^4 + 3x^3 + 4x^2 + 3x + 1"));
+        assert!(palindromic("2x^3 - x^2 - x + 2"));
+        assert!(palindromic("x^2 + 1"));
+        assert!(palindromic("5"));
+
+        assert!(!palindromic("x^4 + 3x^3 + 4x^2 + 3x + 2"));
+        assert!(!palindromic("x^3 - x^2 + x + 1"));
+        // The constant term counts even if it's zero
+        assert!(!palindromic("x^3 + x^2"));
+    }
+
+    #[test]
+    fn signs_at_infinity() {
+        // x^2 - 5 and -x^2 + 5
+        let even = Polynomial::from(vec![(0, Rational::from(-5)), (2, Rational::from(1))]);
+        assert_eq!(
+            (even.sign_at_neg_infinity(), even.sign_at_infinity()),
+            (1, 1)
+        );
+
+        let even = -even;
+        assert_eq!(
+            (even.sign_at_neg_infinity(), even.sign_at_infinity()),
+            (-1, -1)
+        );
+
+        // x^3/2 + 100x^2 and -x^3/2 - 100x^2
+        let odd = Polynomial::from(vec![(2, Rational::from(100)), (3, Rational::new(1, 2))]);
+        assert_eq!(
+            (odd.sign_at_neg_infinity(), odd.sign_at_infinity()),
+            (-1, 1)
+        );
+
+        let odd = -odd;
+        assert_eq!(
+            (odd.sign_at_neg_infinity(), odd.sign_at_infinity()),
+            (1, -1)
+        );
+
+        let constant = Polynomial::from(vec![(0, Rational::from(-3))]);
+        assert_eq!(
+            (constant.sign_at_neg_infinity(), constant.sign_at_infinity()),
+            (-1, -1)
+        );
+
+        assert_eq!(Polynomial::zero().sign_at_infinity(), 0);
+        assert_eq!(Polynomial::zero().sign_at_neg_infinity(), 0);
+    }
+
+    #[test]
+    fn reduce_mod() {
+        // x^2 + 3x + 5 = x^2 + 2 (mod 3)
+        assert_eq!(
+            Polynomial::from(vec![
+                (0, Rational::from(5)),
+                (1, Rational::from(3)),
+                (2, Rational::from(1)),
+            ])
+            .reduce_mod(3),
+            Some(Polynomial::from(vec![
+                (0, Rational::from(2)),
+                (2, Rational::from(1))
+            ]))
+        );
+
+        // -x/2 - 7 = 2x + 3 (mod 5), as 2 * 3 = 1 (mod 5)
+        assert_eq!(
+            Polynomial::from(vec![(0, Rational::from(-7)), (1, Rational::new(-1, 2))])
+                .reduce_mod(5),
+            Some(Polynomial::from(vec![
+                (0, Rational::from(3)),
+                (1, Rational::from(2))
+            ]))
+        );
+
+        assert_eq!(
+            Polynomial::from(vec![(1, Rational::new(1, 6))]).reduce_mod(4),
+            None
+        );
+        assert_eq!(
+            Polynomial::from(vec![(1, Rational::from(4))]).reduce_mod(2),
+            Some(Polynomial::zero())
+        );
+
+        for p in [1, 0, -3] {
+            assert_eq!(
+                Polynomial::from(vec![(1, Rational::from(4))]).reduce_mod(p),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn roots_mod() {
+        // x^2 - 1 = (x - 1)(x + 1)
+        let poly = Polynomial::from(vec![(0, Rational::from(-1)), (2, Rational::from(1))]);
+
+        assert_eq!(poly.roots_mod(5), vec![1, 4]);
+        assert_eq!(poly.roots_mod(2), vec![1]);
+
+        // x^2 + 1 has no roots mod 3, but x^2 + 1 = (x - 2)(x - 3) mod 5
+        let poly = Polynomial::from(vec![(0, Rational::from(1)), (2, Rational::from(1))]);
+
+        assert_eq!(poly.roots_mod(3), vec![]);
+        assert_eq!(poly.roots_mod(5), vec![2, 3]);
+
+        // x/2 = 3x mod 5
+        assert_eq!(
+            Polynomial::from(vec![(1, Rational::new(1, 2))]).roots_mod(5),
+            vec![0]
+        );
+
+        // 3x + 6 is 0 mod 3
+        assert_eq!(
+            Polynomial::from(vec![(0, Rational::from(6)), (1, Rational::from(3))]).roots_mod(3),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "roots_mod: the modulus must be at least 2, got -3")]
+    fn roots_mod_negative_modulus() {
+        Polynomial::from(vec![(1, Rational::from(1))]).roots_mod(-3);
+    }
+
+    #[test]
+    #[should_panic(expected = "roots_mod: the modulus must be at least 2, got 0")]
+    fn roots_mod_zero_modulus() {
+        Polynomial::from(vec![(1, Rational::from(1))]).roots_mod(0);
+    }
+
+    #[test]
+    fn derivative_at() {
+        // 2x^5 - x^3/3 + 4x - 7
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-7)),
+            (1, Rational::from(4)),
+            (3, Rational::new(-1, 3)),
+            (5, Rational::from(2)),
+        ]);
+
+        for x in [Rational::from(0), Rational::from(-2), Rational::new(3, 2)] {
+            let mut derivative = poly.clone();
+
+            for order in 0..=7 {
+                assert_eq!(
+                    poly.derivative_at(x.clone(), order),
+                    derivative.eval(x.clone())
+                );
+
+                derivative = derivative.diff();
+            }
+        }
+
+        assert_eq!(
+            poly.derivative_at(Rational::from(1), 5),
+            Rational::from(240)
+        );
+        assert_eq!(poly.derivative_at(Rational::from(1), 6), Rational::from(0));
+    }
+
+    #[test]
+    fn companion_matrix() {
+        let int = |row: &[i32]| {
+            row.iter()
+                .map(|&entry| Rational::from(entry))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            parser::parse_polynomial_expr("x^2 - 3x + 2")
+                .unwrap()
+                .companion_matrix(),
+            vec![int(&[0, -2]), int(&[1, 3])]
+        );
+
+        // 2x^3 - 4x + 1 is made monic first
+        assert_eq!(
+            parser::parse_polynomial_expr("2x^3 - 4x + 1")
+                .unwrap()
+                .companion_matrix(),
+            vec![
+                vec![Rational::from(0), Rational::from(0), Rational::new(-1, 2)],
+                int(&[1, 0, 2]),
+                int(&[0, 1, 0]),
+            ]
+        );
+
+        assert_eq!(
+            parser::parse_polynomial_expr("x + 5")
+                .unwrap()
+                .companion_matrix(),
+            vec![int(&[-5])]
+        );
+        assert!(Polynomial::from(vec![(0, Rational::from(3))])
+            .companion_matrix()
+            .is_empty());
+        assert!(Polynomial::zero().companion_matrix().is_empty());
+    }
+
     #[test]
     fn diff() {
         assert_eq!(
-            Polynomial::new(HashMap::from([
+            Polynomial::new(BTreeMap::from([
                 (0, Rational::from(1)),
                 (1, Rational::from(2)),
                 (2, Rational::from(3)),
             ]))
             .diff(),
-            Polynomial::new(HashMap::from([
+            Polynomial::new(BTreeMap::from([
                 (0, Rational::from(2)),
                 (1, Rational::from(6)),
             ]))
         );
 
         assert_eq!(
-            Polynomial::new(HashMap::from([
+            Polynomial::new(BTreeMap::from([
                 (0, Rational::from(1)),
                 (2, Rational::from(-5)),
                 (3, Rational::from(69)),
             ]))
             .diff(),
-            Polynomial::new(HashMap::from([
+            Polynomial::new(BTreeMap::from([
                 (1, Rational::from(-5 * 2)),
                 (2, Rational::from(69 * 3)),
             ]))
@@ -1,232 +1,781 @@
-use std::{
+use crate::bigint::{scale_f64, BigInt};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::{
     cmp::Ordering,
     fmt::Display,
     ops::{Add, AddAssign, Div, Mul, Neg, Sub},
 };
 
-fn integer_sqrt(value: i64) -> Option<i64> {
-    if value < 0 {
-        todo!("integer_sqrt: negative roots");
-    }
-
-    // Use binary search to find the integer square root. Adapted from https://en.wikipedia.org/wiki/Integer_square_root#Algorithm_using_binary_search.
-    let mut low = 0;
-    let mut mid;
-    let mut high = value + 1;
-
-    while low != high - 1 {
-        mid = (low + high) / 2;
+fn integer_sqrt(value: i128) -> Option<i128> {
+    integer_nth_root(value, 2)
+}
 
-        if mid * mid <= value {
-            low = mid;
-        } else {
-            high = mid;
-        }
-    }
+fn integer_cbrt(value: i128) -> Option<i128> {
+    integer_nth_root(value, 3)
+}
 
-    if low * low == value {
-        Some(low)
-    } else {
-        None
+/// Find the exact `n`-th root of `value`, if it's an integer. Odd roots of negative values are negative, while even roots
+/// of them don't exist.
+fn integer_nth_root(value: i128, n: u32) -> Option<i128> {
+    if n == 0 || (value < 0 && n.is_multiple_of(2)) {
+        return None;
     }
-}
 
-fn integer_cbrt(value: i64) -> Option<i64> {
-    // Cube root is an odd function meaning that cbrt(-a) = -cbrt(a). So, in order to compute cbrt(-a) we compute cbrt(a) and tack a minus on at the end.
-    let value_abs = value.abs();
+    // An n-th root of an odd function like this one satisfies root(-a) = -root(a), so the search is done on the absolute
+    // value, which always fits into a u128 (unlike -2^127 in an i128)
+    let value_abs = value.unsigned_abs();
 
-    // Use binary search to find the integer cube root. Adapted from https://en.wikipedia.org/wiki/Integer_square_root#Algorithm_using_binary_search.
+    // Use binary search to find the integer root. Adapted from https://en.wikipedia.org/wiki/Integer_square_root#Algorithm_using_binary_search.
+    // A value below 2^128 has an n-th root below 2^(128/n + 1), which bounds the search for anything but tiny values
+    let bound = 128 / n + 1;
     let mut low = 0;
-    let mut mid;
-    let mut high = value_abs + 1;
+    let mut high = if bound < 128 {
+        value_abs.min(1 << bound)
+    } else {
+        value_abs
+    } + 1;
 
     while low != high - 1 {
-        mid = (low + high) / 2;
+        let mid = low + (high - low) / 2;
 
-        if mid.pow(3) <= value_abs {
+        // `mid^n` can still overflow for the first few iterations, in which case it's clearly too big
+        if mid.checked_pow(n).is_some_and(|power| power <= value_abs) {
             low = mid;
         } else {
             high = mid;
         }
     }
 
-    if low.pow(3) == value_abs {
-        Some(if value < 0 { -low } else { low })
+    if low.checked_pow(n) != Some(value_abs) {
+        return None;
+    }
+
+    if value < 0 {
+        0i128.checked_sub_unsigned(low)
     } else {
-        None
+        i128::try_from(low).ok()
     }
 }
 
-fn greatest_common_divisor(mut a: i64, mut b: i64) -> i64 {
+pub(crate) fn greatest_common_divisor(mut a: i128, mut b: i128) -> i128 {
     // Use Euclidean algorithm to find the GCD (https://en.wikipedia.org/wiki/Greatest_common_divisor#Euclidean_algorithm)
     while b != 0 {
         let t = b;
-        b = a % b;
+        // `i128::MIN % -1` overflows even though the remainder is just 0
+        b = a.wrapping_rem(b);
         a = t;
     }
 
     a
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Rational {
-    numer: i64,
-    denom: i64,
+/// An exact rational number, always kept in lowest terms with the sign in the numerator.
+///
+/// Most values have a small numerator and denominator, which are stored inline as `i128`s so that arithmetic on them
+/// never allocates. A result which doesn't fit is promoted to a boxed pair of `BigInt`s instead of overflowing, and
+/// demoted again once it fits, so every value has exactly one representation.
+///
+/// The representation is private, so that every value is constructed through `new` (or an operation which keeps the
+/// form) and no non-canonical value can ever be observed.
+#[derive(Clone, Debug)]
+pub struct Rational(Repr);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Repr {
+    /// The numerator and denominator, which both fit into an `i128`.
+    Small(i128, i128),
+    /// The numerator and denominator, at least one of which doesn't fit into an `i128`.
+    Big(Box<(BigInt, BigInt)>),
 }
 
 impl Rational {
-    pub fn new(mut numer: i64, mut denom: i64) -> Rational {
-        let gcd = greatest_common_divisor(numer, denom).abs();
-
+    pub fn new(numer: i128, denom: i128) -> Rational {
+        // Check this before computing the GCD, as gcd(0, 0) = 0 would otherwise be divided by below.
         if denom == 0 {
+            if numer == 0 {
+                panic!("0/0 is undefined.");
+            }
+
             panic!("denominator cannot be zero.");
         }
 
+        // Reducing only overflows around `i128::MIN`, whose magnitude doesn't fit
+        let reduce = || {
+            let gcd = greatest_common_divisor(numer, denom).checked_abs()?;
+            let (numer, denom) = (numer / gcd, denom / gcd);
+
+            // Keep the sign in the numerator
+            if denom < 0 {
+                Some((numer.checked_neg()?, denom.checked_neg()?))
+            } else {
+                Some((numer, denom))
+            }
+        };
+
+        match reduce() {
+            Some((numer, denom)) => Rational(Repr::Small(numer, denom)),
+            None => Rational::from_bigints(BigInt::from(numer), BigInt::from(denom)),
+        }
+    }
+
+    /// Build a rational from big integer parts, which are reduced to lowest terms just like in [`Rational::new`].
+    pub fn from_bigints(numer: BigInt, denom: BigInt) -> Rational {
+        if denom.is_zero() {
+            if numer.is_zero() {
+                panic!("0/0 is undefined.");
+            }
+
+            panic!("denominator cannot be zero.");
+        }
+
+        let gcd = numer.gcd(&denom);
+        let (mut numer, mut denom) = (&numer / &gcd, &denom / &gcd);
+
         // Make sure the sign is always kept in the numerator.
-        if denom < 0 {
+        if denom.is_negative() {
             numer = -numer;
             denom = -denom;
         }
 
-        Rational {
-            numer: numer / gcd,
-            denom: denom / gcd,
+        Rational::from_reduced(numer, denom)
+    }
+
+    /// Wrap parts which are already in lowest terms, storing them inline if they fit.
+    fn from_reduced(numer: BigInt, denom: BigInt) -> Rational {
+        match (numer.to_i128(), denom.to_i128()) {
+            (Some(numer), Some(denom)) => Rational(Repr::Small(numer, denom)),
+            _ => Rational(Repr::Big(Box::new((numer, denom)))),
+        }
+    }
+
+    /// Get the numerator, in lowest terms and carrying the sign.
+    pub fn numer(&self) -> BigInt {
+        match &self.0 {
+            Repr::Small(numer, _) => BigInt::from(*numer),
+            Repr::Big(parts) => parts.0.clone(),
         }
     }
 
+    /// Get the denominator, in lowest terms and always positive.
+    pub fn denom(&self) -> BigInt {
+        match &self.0 {
+            Repr::Small(_, denom) => BigInt::from(*denom),
+            Repr::Big(parts) => parts.1.clone(),
+        }
+    }
+
+    /// Get the numerator and denominator as `i128`s without allocating, or `None` if either of them doesn't fit.
+    pub fn to_i128_parts(&self) -> Option<(i128, i128)> {
+        match self.0 {
+            Repr::Small(numer, denom) => Some((numer, denom)),
+            Repr::Big(_) => None,
+        }
+    }
+
+    /// Get the numerator and denominator as big integers, for the slow path of operations.
+    fn to_bigints(&self) -> (BigInt, BigInt) {
+        (self.numer(), self.denom())
+    }
+
     pub fn reciprocal(&self) -> Self {
-        Rational {
-            numer: self.denom,
-            denom: self.numer,
+        if self.is_zero() {
+            panic!("cannot take the reciprocal of zero");
+        }
+
+        match &self.0 {
+            // The parts are already coprime, so this just moves the sign back into the numerator
+            Repr::Small(numer, denom) => Rational::new(*denom, *numer),
+            Repr::Big(parts) => {
+                let (numer, denom) = &**parts;
+
+                if numer.is_negative() {
+                    Rational::from_reduced(-denom, -numer)
+                } else {
+                    Rational::from_reduced(denom.clone(), numer.clone())
+                }
+            }
         }
     }
 
     pub fn sqrt(&self) -> Rational {
-        Rational {
-            numer: integer_sqrt(self.numer)
-                .expect("todo: irrational square roots not supported yet"),
-            denom: integer_sqrt(self.denom)
-                .expect("todo: irrational square roots not supported yet"),
+        self.checked_sqrt()
+            .expect("todo: irrational square roots not supported yet")
+    }
+
+    /// Get the square root, returning `None` if it's irrational or imaginary.
+    pub fn checked_sqrt(&self) -> Option<Rational> {
+        if self.is_negative() {
+            return None;
+        }
+
+        // The value is in lowest terms, so it's a perfect square exactly if the numerator and denominator both are
+        match &self.0 {
+            Repr::Small(numer, denom) => Some(Rational(Repr::Small(
+                integer_sqrt(*numer)?,
+                integer_sqrt(*denom)?,
+            ))),
+            Repr::Big(_) => self.checked_root(2),
         }
     }
 
     pub fn cbrt(&self) -> Rational {
-        Rational {
-            numer: integer_cbrt(self.numer).expect("todo: irrational cube roots not supported yet"),
-            denom: integer_cbrt(self.denom).expect("todo: irrational cube roots not supported yet"),
+        match self.0 {
+            Repr::Small(numer, denom) => Rational(Repr::Small(
+                integer_cbrt(numer).expect("todo: irrational cube roots not supported yet"),
+                integer_cbrt(denom).expect("todo: irrational cube roots not supported yet"),
+            )),
+            Repr::Big(_) => self
+                .checked_root(3)
+                .expect("todo: irrational cube roots not supported yet"),
         }
     }
 
+    /// Get the exact `n`-th root using big integers, returning `None` if it's irrational or imaginary.
+    fn checked_root(&self, n: u32) -> Option<Rational> {
+        // Odd roots of negative values are the negated roots of their magnitude
+        let exact_root = |value: &BigInt| {
+            if value.is_negative() && n.is_multiple_of(2) {
+                return None;
+            }
+
+            let root = value.abs().nth_root(n);
+            let root = if value.is_negative() { -root } else { root };
+
+            (root.pow(n) == *value).then_some(root)
+        };
+        let (numer, denom) = self.to_bigints();
+
+        // The value is in lowest terms, so the roots of its parts are as well
+        Some(Rational::from_reduced(
+            exact_root(&numer)?,
+            exact_root(&denom)?,
+        ))
+    }
+
     pub fn pow(&self, exponent: u32) -> Self {
-        Rational {
-            numer: self.numer.pow(exponent as u32),
-            denom: self.denom.pow(exponent as u32),
+        // Powers of coprime parts stay coprime, so the result doesn't need to be reduced
+        if let Repr::Small(numer, denom) = self.0 {
+            if let (Some(numer), Some(denom)) =
+                (numer.checked_pow(exponent), denom.checked_pow(exponent))
+            {
+                return Rational(Repr::Small(numer, denom));
+            }
         }
+
+        let (numer, denom) = self.to_bigints();
+
+        Rational::from_reduced(numer.pow(exponent), denom.pow(exponent))
     }
 
-    pub fn reduce(&self) -> Rational {
-        let gcd = greatest_common_divisor(self.numer, self.denom);
+    /// Raise to a rational power `p/q` by taking the `q`-th root and then the `p`-th power, e.g. `(4/9)^(3/2) = 8/27`.
+    ///
+    /// Returns `None` if the result is irrational or imaginary (like even roots of negative values) or undefined (like
+    /// negative powers of 0).
+    pub fn rpow(&self, exponent: Rational) -> Option<Rational> {
+        let (numer, denom) = exponent.to_i128_parts()?;
+
+        let root = match (&self.0, u32::try_from(denom).ok()?) {
+            (_, 1) => self.clone(),
+            (Repr::Big(_), n) => self.checked_root(n)?,
+            (&Repr::Small(numer, denom), n) => Rational(Repr::Small(
+                integer_nth_root(numer, n)?,
+                integer_nth_root(denom, n)?,
+            )),
+        };
+
+        let result = root.pow(u32::try_from(numer.unsigned_abs()).ok()?);
 
-        Rational {
-            numer: self.numer / gcd,
-            denom: self.denom / gcd,
+        match (numer < 0, result.is_zero()) {
+            (true, true) => None,
+            (true, false) => Some(result.reciprocal()),
+            (false, _) => Some(result),
         }
     }
 
+    /// Bring the value into canonical form, i.e. lowest terms with the sign in the numerator.
+    ///
+    /// Every value is already kept in that form, so this is just a copy.
+    pub fn reduce(&self) -> Rational {
+        self.clone()
+    }
+
     pub fn abs(&self) -> Self {
-        Rational {
-            numer: self.numer.abs(),
-            denom: self.denom,
+        if self.is_negative() {
+            -self
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Get the (non-negative) greatest common divisor of two rationals, i.e. the largest rational `g` such that both
+    /// are integer multiples of `g`. This is the GCD of the numerators over the LCM of the denominators.
+    pub fn gcd(&self, other: &Rational) -> Rational {
+        if let (Repr::Small(a, b), Repr::Small(c, d)) = (&self.0, &other.0) {
+            let numer = greatest_common_divisor(*a, *c).checked_abs();
+            let denom = (b / greatest_common_divisor(*b, *d)).checked_mul(*d);
+
+            if let (Some(numer), Some(denom)) = (numer, denom) {
+                return Rational::new(numer, denom);
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.to_bigints(), other.to_bigints());
+
+        Rational::from_bigints(a.gcd(&c), &(&b / &b.gcd(&d)) * &d)
+    }
+
+    /// Get the (non-negative) least common multiple of two rationals, i.e. the smallest rational that is an integer
+    /// multiple of both. This is the LCM of the numerators over the GCD of the denominators.
+    pub fn lcm(&self, other: &Rational) -> Rational {
+        if self.is_zero() || other.is_zero() {
+            return Rational::from(0);
+        }
+
+        if let (Repr::Small(a, b), Repr::Small(c, d)) = (&self.0, &other.0) {
+            let numer = (a / greatest_common_divisor(*a, *c))
+                .checked_mul(*c)
+                .and_then(i128::checked_abs);
+
+            if let Some(numer) = numer {
+                return Rational::new(numer, greatest_common_divisor(*b, *d));
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.to_bigints(), other.to_bigints());
+
+        Rational::from_bigints((&(&a / &a.gcd(&c)) * &c).abs(), b.gcd(&d))
+    }
+
+    /// Get the smaller of two values.
+    pub fn min(self, other: Rational) -> Rational {
+        Ord::min(self, other)
+    }
+
+    /// Get the larger of two values.
+    pub fn max(self, other: Rational) -> Rational {
+        Ord::max(self, other)
+    }
+
+    /// Get the sign of the value as `-1`, `0` or `1`.
+    pub fn signum(&self) -> i32 {
+        match &self.0 {
+            Repr::Small(numer, _) => numer.signum() as i32,
+            // A big value is never zero
+            Repr::Big(parts) if parts.0.is_negative() => -1,
+            Repr::Big(_) => 1,
+        }
+    }
+
+    /// Check whether the value is greater than 0. The sign is always kept in the numerator, so this is cheaper than
+    /// comparing against `Rational::from(0)`.
+    pub fn is_positive(&self) -> bool {
+        self.signum() > 0
+    }
+
+    /// Check whether the value is less than 0.
+    pub fn is_negative(&self) -> bool {
+        self.signum() < 0
+    }
+
+    /// Check whether the value is 0.
+    pub fn is_zero(&self) -> bool {
+        self.signum() == 0
+    }
+
+    /// Get the value as an integer, or `None` if it isn't one or doesn't fit into an `i128`.
+    pub fn as_integer(&self) -> Option<i128> {
+        match self.0 {
+            Repr::Small(numer, 1) => Some(numer),
+            _ => None,
+        }
+    }
+
+    /// Check whether the value is an integer.
+    pub fn is_integer(&self) -> bool {
+        match &self.0 {
+            Repr::Small(_, denom) => *denom == 1,
+            Repr::Big(parts) => parts.1 == BigInt::from(1),
+        }
+    }
+
+    /// Format as LaTeX, where fractions are written like `-\frac{1}{2}`.
+    pub fn to_latex(&self) -> String {
+        if self.is_integer() {
+            return format!("{}", self);
+        }
+
+        let (numer, denom) = self.to_bigints();
+
+        format!(
+            "{}\\frac{{{}}}{{{}}}",
+            if self.is_negative() { "-" } else { "" },
+            numer.abs(),
+            denom
+        )
+    }
+
+    /// Split into an integer part and a proper fraction, both with the same sign, e.g. `-7/3` is `(-2, -1/3)`.
+    pub fn to_mixed(&self) -> (BigInt, Rational) {
+        let (numer, denom) = self.to_bigints();
+        let (whole, remainder) = numer.div_rem(&denom);
+
+        // The remainder is still coprime to the denominator
+        (whole, Rational::from_reduced(remainder, denom))
+    }
+
+    /// Expand into the coefficients `[a0; a1, a2, ...]` of the (finite) simple continued fraction
+    /// `a0 + 1 / (a1 + 1 / (a2 + ...))` (https://en.wikipedia.org/wiki/Continued_fraction).
+    pub fn to_continued_fraction(&self) -> Vec<BigInt> {
+        let mut coeffs = Vec::new();
+        let (mut numer, mut denom) = self.to_bigints();
+
+        // This is just the Euclidean algorithm, keeping track of the quotients along the way. The quotients are rounded
+        // down, so that every coefficient after the first is positive.
+        while !denom.is_zero() {
+            let (mut quotient, mut remainder) = numer.div_rem(&denom);
+
+            if remainder.is_negative() {
+                quotient = quotient - BigInt::from(1);
+                remainder = &remainder + &denom;
+            }
+
+            coeffs.push(quotient);
+
+            (numer, denom) = (denom, remainder);
+        }
+
+        coeffs
+    }
+
+    /// Rebuild a rational from the coefficients `[a0; a1, a2, ...]` of its simple continued fraction, as given by
+    /// [`Rational::to_continued_fraction`].
+    pub fn from_continued_fraction(coeffs: &[BigInt]) -> Rational {
+        let (last, rest) = coeffs
+            .split_last()
+            .expect("continued fraction must have at least 1 coefficient");
+
+        rest.iter()
+            .rev()
+            .fold(Rational::from(last.clone()), |value, coeff| {
+                Rational::from(coeff.clone()) + value.reciprocal()
+            })
+    }
+
+    /// Find the rational closest to `value` whose denominator is at most `max_denom`.
+    ///
+    /// The best approximation is always either a convergent or a semiconvergent of the continued fraction of `value`
+    /// (https://en.wikipedia.org/wiki/Continued_fraction#Best_rational_approximations).
+    ///
+    /// If the convergents stop fitting into an `i128` before reaching `max_denom`, the last one that does is returned.
+    ///
+    /// Requires the `std` feature, as `core` doesn't provide `f64::floor`.
+    #[cfg(feature = "std")]
+    pub fn approximate(value: f64, max_denom: i128) -> Rational {
+        assert!(value.is_finite(), "cannot approximate {}", value);
+        assert!(max_denom >= 1, "maximum denominator must be positive");
+
+        // Floats this large are always integers, which may not even fit into an `i128`
+        if value.abs() >= (1u64 << 52) as f64 {
+            return Rational::from_f64(value).unwrap();
+        }
+
+        // The previous two convergents h/k, starting with the conventional 0/1 and 1/0
+        let (mut h0, mut h1) = (0, 1);
+        let (mut k0, mut k1) = (1, 0);
+        let mut x = value;
+
+        loop {
+            let coeff = x.floor();
+            let next = |previous: i128, before: i128| {
+                (coeff as i128)
+                    .checked_mul(previous)
+                    .and_then(|product| product.checked_add(before))
+            };
+
+            // The first convergent is just the integer part, so there always is a previous one to fall back to
+            let (Some(h2), Some(k2)) = (next(h1, h0), next(k1, k0)) else {
+                return Rational::new(h1, k1);
+            };
+
+            if k2 > max_denom {
+                // The next convergent's denominator is too large, so the best that can be done is either the current
+                // convergent or the semiconvergent with the largest allowed denominator
+                let t = (max_denom - k0) / k1;
+                let convergent = Rational::new(h1, k1);
+                let semiconvergent = Rational::new(t * h1 + h0, t * k1 + k0);
+
+                return if (semiconvergent.to_f64() - value).abs()
+                    < (convergent.to_f64() - value).abs()
+                {
+                    semiconvergent
+                } else {
+                    convergent
+                };
+            }
+
+            (h0, h1) = (h1, h2);
+            (k0, k1) = (k1, k2);
+
+            // The value has been represented exactly (up to floating-point precision)
+            if x - coeff < 1e-12 {
+                return Rational::new(h1, k1);
+            }
+
+            x = 1.0 / (x - coeff);
         }
     }
 
-    pub fn as_integer(&self) -> Option<i64> {
-        if self.denom == 1 {
-            Some(self.numer)
+    /// Get the exact value stored in a float, e.g. `0.1` is really `3602879701896397/36028797018963968`.
+    ///
+    /// Every finite float is a rational, even huge ones like `1e300` and subnormal ones like `5e-324`, so this only
+    /// returns `None` for NaN and infinities.
+    pub fn from_f64(value: f64) -> Option<Rational> {
+        // Decompose the IEEE-754 double into `mantissa * 2^exponent`
+        // (https://en.wikipedia.org/wiki/Double-precision_floating-point_format)
+        let bits = value.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+
+        let (mut mantissa, mut exponent) = match biased_exponent {
+            0x7ff => return None,
+            // Subnormal numbers don't have the implicit leading 1
+            0 => (fraction, -1074),
+            _ => (fraction | (1 << 52), biased_exponent - 1075),
+        };
+
+        if mantissa == 0 {
+            return Some(Rational::from(0));
+        }
+
+        // Cancel out common factors of 2 up front, so that the result is already reduced
+        exponent += mantissa.trailing_zeros() as i32;
+        mantissa >>= mantissa.trailing_zeros();
+
+        let numer = if bits >> 63 == 1 {
+            -(mantissa as i128)
         } else {
-            None
+            mantissa as i128
+        };
+
+        // The parts are coprime, as the mantissa is odd and everything else is a power of 2
+        let power_of_two = BigInt::from(2).pow(exponent.unsigned_abs());
+
+        Some(if exponent >= 0 {
+            Rational::from_reduced(&BigInt::from(numer) * &power_of_two, BigInt::from(1))
+        } else {
+            Rational::from_reduced(BigInt::from(numer), power_of_two)
+        })
+    }
+
+    /// Divide two rationals, returning `None` if `other` is zero.
+    pub fn checked_div(&self, other: &Rational) -> Option<Rational> {
+        if other.is_zero() {
+            return None;
         }
+
+        Some(self * &other.reciprocal())
     }
 
-    pub fn to_f64(self) -> f64 {
-        self.numer as f64 / self.denom as f64
+    pub fn to_f64(&self) -> f64 {
+        match &self.0 {
+            Repr::Small(numer, denom) => *numer as f64 / *denom as f64,
+            Repr::Big(parts) => {
+                // Dividing the top bits of both parts keeps huge values from overflowing to `inf / inf`
+                let (numer, numer_exponent) = parts.0.to_scaled_f64();
+                let (denom, denom_exponent) = parts.1.to_scaled_f64();
+
+                scale_f64(numer / denom, numer_exponent - denom_exponent)
+            }
+        }
     }
 }
 
 impl From<i32> for Rational {
     fn from(x: i32) -> Self {
-        Rational {
-            numer: x as i64,
-            denom: 1,
-        }
+        Rational(Repr::Small(x as i128, 1))
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(x: i64) -> Self {
+        Rational(Repr::Small(x as i128, 1))
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(x: i128) -> Self {
+        Rational(Repr::Small(x, 1))
+    }
+}
+
+impl From<(i32, i32)> for Rational {
+    fn from((numer, denom): (i32, i32)) -> Self {
+        Rational::new(numer as i128, denom as i128)
     }
 }
 
 impl From<u32> for Rational {
     fn from(x: u32) -> Self {
-        Rational {
-            numer: x as i64,
-            denom: 1,
-        }
+        Rational(Repr::Small(x as i128, 1))
     }
 }
 
-impl Add for Rational {
-    type Output = Self;
+impl From<BigInt> for Rational {
+    fn from(x: BigInt) -> Self {
+        Rational::from_reduced(x, BigInt::from(1))
+    }
+}
 
-    fn add(self, other: Self) -> Self {
-        Rational::new(
-            self.numer * other.denom + self.denom * other.numer,
-            self.denom * other.denom,
-        )
+// The arithmetic is implemented on references so that accumulating loops don't have to clone their operands, with the
+// by-value operators delegating to them. Each operation first tries the allocation-free `i128` path and only falls back
+// to big integers if that overflows.
+
+impl Add for &Rational {
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Rational {
+        if let (&Repr::Small(a, b), &Repr::Small(c, d)) = (&self.0, &other.0) {
+            // a/b + c/d = (ad + bc)/bd
+            let fast_path = || {
+                Some((
+                    a.checked_mul(d)?.checked_add(b.checked_mul(c)?)?,
+                    b.checked_mul(d)?,
+                ))
+            };
+
+            if let Some((numer, denom)) = fast_path() {
+                return Rational::new(numer, denom);
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.to_bigints(), other.to_bigints());
+
+        Rational::from_bigints(&a * &d + &b * &c, &b * &d)
     }
 }
 
-impl AddAssign for Rational {
-    fn add_assign(&mut self, other: Self) {
-        *self = *self + other;
+impl Sub for &Rational {
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Rational {
+        if let (&Repr::Small(a, b), &Repr::Small(c, d)) = (&self.0, &other.0) {
+            // a/b - c/d = (ad - bc)/bd
+            let fast_path = || {
+                Some((
+                    a.checked_mul(d)?.checked_sub(b.checked_mul(c)?)?,
+                    b.checked_mul(d)?,
+                ))
+            };
+
+            if let Some((numer, denom)) = fast_path() {
+                return Rational::new(numer, denom);
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.to_bigints(), other.to_bigints());
+
+        Rational::from_bigints(&a * &d - &b * &c, &b * &d)
     }
 }
 
-impl Sub for Rational {
-    type Output = Self;
+impl Neg for &Rational {
+    type Output = Rational;
 
-    fn sub(self, other: Self) -> Self {
-        self + -other
+    fn neg(self) -> Rational {
+        match &self.0 {
+            // Only `-i128::MIN` doesn't fit
+            &Repr::Small(numer, denom) => match numer.checked_neg() {
+                Some(numer) => Rational(Repr::Small(numer, denom)),
+                None => Rational::from_reduced(-BigInt::from(numer), BigInt::from(denom)),
+            },
+            Repr::Big(parts) => Rational::from_reduced(-&parts.0, parts.1.clone()),
+        }
     }
 }
 
-impl Neg for Rational {
+impl Mul for &Rational {
     type Output = Rational;
 
-    fn neg(self) -> Self {
-        Rational::new(-self.numer, self.denom)
+    fn mul(self, other: &Rational) -> Rational {
+        if let (&Repr::Small(a, b), &Repr::Small(c, d)) = (&self.0, &other.0) {
+            if let (Some(numer), Some(denom)) = (a.checked_mul(c), b.checked_mul(d)) {
+                return Rational::new(numer, denom);
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.to_bigints(), other.to_bigints());
+
+        Rational::from_bigints(&a * &c, &b * &d)
     }
 }
 
-impl Mul for Rational {
+impl Div for &Rational {
     type Output = Rational;
 
-    fn mul(self, rhs: Rational) -> Self {
-        Rational::new(self.numer * rhs.numer, self.denom * rhs.denom)
+    fn div(self, other: &Rational) -> Rational {
+        self.checked_div(other).expect("attempt to divide by zero")
     }
 }
 
-impl Div for Rational {
+/// Implement a by-value binary operator (and the mixed by-value/by-reference ones) by delegating to the implementation
+/// on references.
+macro_rules! forward_binary_op {
+    ($op:ident, $method:ident) => {
+        impl $op for Rational {
+            type Output = Rational;
+
+            fn $method(self, other: Rational) -> Rational {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $op<&Rational> for Rational {
+            type Output = Rational;
+
+            fn $method(self, other: &Rational) -> Rational {
+                (&self).$method(other)
+            }
+        }
+
+        impl $op<Rational> for &Rational {
+            type Output = Rational;
+
+            fn $method(self, other: Rational) -> Rational {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binary_op!(Add, add);
+forward_binary_op!(Sub, sub);
+forward_binary_op!(Mul, mul);
+forward_binary_op!(Div, div);
+
+impl Neg for Rational {
     type Output = Rational;
 
-    #[allow(clippy::suspicious_arithmetic_impl)]
-    fn div(self, rhs: Rational) -> Self {
-        self * rhs.reciprocal()
+    fn neg(self) -> Rational {
+        -&self
+    }
+}
+
+impl AddAssign<&Rational> for Rational {
+    fn add_assign(&mut self, other: &Rational) {
+        *self = &*self + other;
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, other: Rational) {
+        *self += &other;
     }
 }
 
 impl PartialEq for Rational {
     fn eq(&self, other: &Self) -> bool {
-        let self_reduced = self.reduce();
-        let other_reduced = other.reduce();
-
-        self_reduced.numer == other_reduced.numer && self_reduced.denom == other_reduced.denom
+        // Canonical forms are unique (including whether the parts are stored inline), so equal values have equal parts
+        self.0 == other.0
     }
 }
 
@@ -240,19 +789,54 @@ impl PartialOrd for Rational {
 
 impl Ord for Rational {
     fn cmp(&self, other: &Self) -> Ordering {
-        let ad = self.numer * other.denom;
-        let bc = self.denom * other.numer;
+        // Cross-multiplying preserves the order because both denominators are positive
+        if let (&Repr::Small(a, b), &Repr::Small(c, d)) = (&self.0, &other.0) {
+            if let (Some(ad), Some(bc)) = (a.checked_mul(d), b.checked_mul(c)) {
+                return ad.cmp(&bc);
+            }
+        }
+
+        // Compare the cross products as big integers if they overflow
+        let ((a, b), (c, d)) = (self.to_bigints(), other.to_bigints());
+
+        (&a * &d).cmp(&(&b * &c))
+    }
+}
+
+impl PartialEq<i32> for Rational {
+    fn eq(&self, other: &i32) -> bool {
+        // Values are always in lowest terms, so only integers stored inline can be equal
+        self.to_i128_parts() == Some((*other as i128, 1))
+    }
+}
 
-        ad.cmp(&bc)
+impl PartialOrd<i32> for Rational {
+    fn partial_cmp(&self, other: &i32) -> Option<Ordering> {
+        Some(self.cmp(&Rational::from(*other)))
     }
 }
 
 impl Display for Rational {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if self.denom == 1 {
-            write!(f, "{}", self.numer)
-        } else {
-            write!(f, "{}/{}", self.numer, self.denom)
+    /// Display as an improper fraction like `7/3`, or as a mixed number like `2 1/3` if the alternate flag (`{:#}`) is
+    /// used.
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if f.alternate() {
+            let (whole, fraction) = self.to_mixed();
+
+            return if fraction.is_zero() {
+                write!(f, "{}", whole)
+            } else if whole.is_zero() {
+                write!(f, "{}", fraction)
+            } else {
+                write!(f, "{} {}", whole, fraction.abs())
+            };
+        }
+
+        match &self.0 {
+            Repr::Small(numer, 1) => write!(f, "{}", numer),
+            Repr::Small(numer, denom) => write!(f, "{}/{}", numer, denom),
+            Repr::Big(parts) if parts.1 == BigInt::from(1) => write!(f, "{}", parts.0),
+            Repr::Big(parts) => write!(f, "{}/{}", parts.0, parts.1),
         }
     }
 }
@@ -269,11 +853,68 @@ mod tests {
         assert_eq!(Rational::new(-8, -3), Rational::new(8, 3));
     }
 
+    #[test]
+    #[should_panic(expected = "0/0 is undefined.")]
+    fn zero_over_zero_panics() {
+        Rational::new(0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "denominator cannot be zero.")]
+    fn zero_denominator_panics() {
+        Rational::new(1, 0);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Rational::from((3, 6)), Rational::new(1, 2));
+        assert_eq!(Rational::from((4, -2)), Rational::from(-2));
+        assert_eq!(Rational::from(5i64), Rational::new(5, 1));
+        assert_eq!(Rational::from(i128::MAX), Rational::new(i128::MAX, 1));
+    }
+
     #[test]
     fn sqrt() {
         assert_eq!(Rational::new(16, 1).sqrt(), Rational::new(4, 1));
         assert_eq!(Rational::new(1, 4).sqrt(), Rational::new(1, 2));
         assert_eq!(Rational::new(16, 4).sqrt(), Rational::from(2));
+        assert_eq!(Rational::new(8, 2).sqrt(), Rational::from(2));
+        assert_eq!(Rational::new(18, 8).sqrt(), Rational::new(3, 2));
+    }
+
+    #[test]
+    fn large_roots() {
+        assert_eq!(
+            Rational::from(2_000_000_011i128 * 2_000_000_011).sqrt(),
+            Rational::from(2_000_000_011)
+        );
+        assert_eq!(
+            Rational::from(10i128.pow(36)).sqrt(),
+            Rational::from(10i128.pow(18))
+        );
+        assert_eq!(Rational::from(i128::MAX).checked_sqrt(), None);
+        assert_eq!(Rational::from(10i128.pow(36) + 1).checked_sqrt(), None);
+
+        assert_eq!(
+            Rational::from(-(2_000_011i128.pow(3))).cbrt(),
+            Rational::from(-2_000_011)
+        );
+        assert_eq!(
+            Rational::from(10i128.pow(36)).cbrt(),
+            Rational::from(10i128.pow(12))
+        );
+        assert_eq!(integer_cbrt(i128::MIN), None);
+        assert_eq!(integer_cbrt(i128::MAX), None);
+    }
+
+    #[test]
+    fn checked_sqrt() {
+        assert_eq!(
+            Rational::new(9, 4).checked_sqrt(),
+            Some(Rational::new(3, 2))
+        );
+        assert_eq!(Rational::from(2).checked_sqrt(), None);
+        assert_eq!(Rational::from(-4).checked_sqrt(), None);
     }
 
     #[test]
@@ -345,6 +986,420 @@ mod tests {
         );
     }
 
+    #[test]
+    fn checked_division_by_zero() {
+        assert_eq!(Rational::from(1).checked_div(&Rational::from(0)), None);
+        assert_eq!(Rational::from(0).checked_div(&Rational::from(0)), None);
+        assert_eq!(Rational::from(1).checked_div(&Rational::new(0, 5)), None);
+        assert_eq!(
+            Rational::from(1).checked_div(&Rational::new(2, 3)),
+            Some(Rational::new(3, 2))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn division_by_zero_panics() {
+        let _ = Rational::from(1) / Rational::from(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot take the reciprocal of zero")]
+    fn reciprocal_of_zero_panics() {
+        Rational::from(0).reciprocal();
+    }
+
+    #[test]
+    fn reduce() {
+        for rational in [
+            Rational::new(-6, 4),
+            Rational::new(6, -4),
+            Rational::new(3, 5),
+            Rational::from(0),
+            Rational::new(-2, 3).reciprocal(),
+            Rational::new(4, 6).pow(2),
+        ] {
+            let reduced = rational.reduce();
+
+            assert_eq!(reduced.reduce(), reduced);
+            assert_eq!(reduced.reduce().numer(), reduced.numer());
+            assert_eq!(reduced.reduce().denom(), reduced.denom());
+            assert!(reduced.denom() > BigInt::zero());
+        }
+
+        assert_eq!(
+            Rational::new(-2, 3).reciprocal().reduce().to_i128_parts(),
+            Some((-3, 2))
+        );
+    }
+
+    #[test]
+    fn values_are_always_canonical() {
+        let values = [
+            Rational::new(-6, 4),
+            Rational::new(6, -4),
+            Rational::new(-6, -4),
+            Rational::new(0, -7),
+            Rational::new(-2, 3).reciprocal(),
+            Rational::new(-4, 6).pow(3),
+            Rational::new(18, 8).sqrt(),
+            Rational::new(-16, 54).cbrt(),
+            Rational::new(-5, 3).abs(),
+            Rational::new(1, 6) + Rational::new(1, 3),
+            Rational::new(1, 6) - Rational::new(2, 3),
+            Rational::new(2, 3) * Rational::new(-3, 4),
+            Rational::new(2, 3) / Rational::new(-4, 9),
+            -Rational::new(2, -6),
+            Rational::from_continued_fraction(&[-3, 1, 2].map(BigInt::from)),
+            Rational::from_f64(-0.375).unwrap(),
+            Rational::from(i128::MAX) + Rational::from(1),
+            Rational::new(1, i128::MAX) * Rational::new(-2, i128::MAX),
+            Rational::from(i128::MIN).reciprocal(),
+            -Rational::from(i128::MIN),
+        ];
+
+        for value in values {
+            assert!(
+                value.denom() > BigInt::zero(),
+                "{:?} has a non-positive denominator",
+                value
+            );
+            assert_eq!(
+                value.numer().gcd(&value.denom()),
+                BigInt::from(1),
+                "{:?} isn't in lowest terms",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn numer_and_denom() {
+        assert_eq!(Rational::new(6, -4).numer(), BigInt::from(-3));
+        assert_eq!(Rational::new(6, -4).denom(), BigInt::from(2));
+        assert_eq!(Rational::new(-2, 3).reciprocal().numer(), BigInt::from(-3));
+        assert_eq!(Rational::new(-2, 3).reciprocal().denom(), BigInt::from(2));
+        assert_eq!(Rational::new(6, -4).to_i128_parts(), Some((-3, 2)));
+
+        let big = Rational::from(i128::MAX) * Rational::new(2, 3);
+        assert_eq!(big.numer(), BigInt::from(i128::MAX) * BigInt::from(2));
+        assert_eq!(big.denom(), BigInt::from(3));
+        assert_eq!(big.to_i128_parts(), None);
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        let (a, b) = (Rational::new(1, 6), Rational::new(1, 4));
+
+        assert_eq!(a.gcd(&b), Rational::new(1, 12));
+        assert_eq!(a.lcm(&b), Rational::new(1, 2));
+
+        // 2/3 = 4 * 1/6 and 3/2 = 9 * 1/6, while 6 = 9 * 2/3 = 4 * 3/2
+        let (a, b) = (Rational::new(-2, 3), Rational::new(3, 2));
+        assert_eq!(a.gcd(&b), Rational::new(1, 6));
+        assert_eq!(a.lcm(&b), Rational::from(6));
+
+        // Integers work as usual, and 0 is a multiple of everything
+        assert_eq!(
+            Rational::from(12).gcd(&Rational::from(18)),
+            Rational::from(6)
+        );
+        assert_eq!(
+            Rational::from(4).lcm(&Rational::from(6)),
+            Rational::from(12)
+        );
+        assert_eq!(
+            Rational::from(0).gcd(&Rational::new(-3, 4)),
+            Rational::new(3, 4)
+        );
+        assert_eq!(
+            Rational::from(0).lcm(&Rational::new(3, 4)),
+            Rational::from(0)
+        );
+    }
+
+    #[test]
+    fn min_and_max() {
+        assert_eq!(
+            Rational::new(1, 2).min(Rational::new(2, 3)),
+            Rational::new(1, 2)
+        );
+        assert_eq!(
+            Rational::new(1, 2).max(Rational::new(2, 3)),
+            Rational::new(2, 3)
+        );
+        assert_eq!(
+            Rational::new(-1, 2).min(Rational::new(-2, 3)),
+            Rational::new(-2, 3)
+        );
+        assert_eq!(
+            Rational::new(-1, 2).max(Rational::new(-2, 3)),
+            Rational::new(-1, 2)
+        );
+        assert_eq!(
+            Rational::new(-3, 4).min(Rational::new(1, 4)),
+            Rational::new(-3, 4)
+        );
+        assert_eq!(
+            Rational::new(2, 4).min(Rational::new(1, 2)),
+            Rational::new(1, 2)
+        );
+        assert_eq!(
+            Rational::new(2, 4).max(Rational::new(1, 2)),
+            Rational::new(1, 2)
+        );
+    }
+
+    #[test]
+    fn signum() {
+        assert_eq!(Rational::new(3, 4).signum(), 1);
+        assert_eq!(Rational::new(-3, 4).signum(), -1);
+        assert_eq!(Rational::new(3, -4).signum(), -1);
+        assert_eq!(Rational::from(0).signum(), 0);
+        assert_eq!(Rational::new(-1, 2).reciprocal().signum(), -1);
+    }
+
+    #[test]
+    fn sign_predicates() {
+        for (value, sign) in [
+            (Rational::new(3, 4), 1),
+            (Rational::new(3, -4), -1),
+            (Rational::new(-3, -4), 1),
+            (Rational::from(0), 0),
+            (Rational::new(0, -5), 0),
+            (Rational::from(i128::MIN), -1),
+        ] {
+            assert_eq!(value.is_positive(), sign == 1);
+            assert_eq!(value.is_negative(), sign == -1);
+            assert_eq!(value.is_zero(), sign == 0);
+            assert_eq!(value.is_positive(), value > 0);
+        }
+    }
+
+    #[test]
+    fn as_integer() {
+        assert_eq!(Rational::from(-7).as_integer(), Some(-7));
+        assert_eq!(Rational::new(1, 2).as_integer(), None);
+        assert_eq!(Rational::new(6, 2).as_integer(), Some(3));
+
+        assert!(Rational::new(12, 4).is_integer());
+        assert!(Rational::new(0, 5).is_integer());
+        assert!(!Rational::new(5, 4).is_integer());
+    }
+
+    #[test]
+    fn continued_fractions() {
+        assert_eq!(
+            Rational::new(355, 113).to_continued_fraction(),
+            [3, 7, 16].map(BigInt::from)
+        );
+        assert_eq!(
+            Rational::from_continued_fraction(&[3, 7, 16].map(BigInt::from)),
+            Rational::new(355, 113)
+        );
+
+        assert_eq!(
+            Rational::new(-7, 3).to_continued_fraction(),
+            [-3, 1, 2].map(BigInt::from)
+        );
+        assert_eq!(
+            Rational::from_continued_fraction(&[-3, 1, 2].map(BigInt::from)),
+            Rational::new(-7, 3)
+        );
+
+        assert_eq!(Rational::from(5).to_continued_fraction(), [BigInt::from(5)]);
+        assert_eq!(
+            Rational::from_continued_fraction(&[BigInt::from(5)]),
+            Rational::from(5)
+        );
+
+        // Values past i128 round-trip too, even with coefficients that don't fit themselves
+        let big = Rational::from_bigints(
+            BigInt::from(i128::MAX).pow(2) + BigInt::from(7),
+            BigInt::from(i128::MAX) - BigInt::from(2),
+        );
+        assert_eq!(big.to_i128_parts(), None);
+
+        let coeffs = big.to_continued_fraction();
+        assert!(coeffs[0] > BigInt::from(i128::MAX));
+        assert_eq!(Rational::from_continued_fraction(&coeffs), big);
+        assert_eq!(
+            Rational::from_continued_fraction(&(-&big).to_continued_fraction()),
+            -big
+        );
+    }
+
+    #[test]
+    fn from_f64() {
+        assert_eq!(Rational::from_f64(0.5), Some(Rational::new(1, 2)));
+        assert_eq!(Rational::from_f64(-2.5), Some(Rational::new(-5, 2)));
+        assert_eq!(Rational::from_f64(3.0), Some(Rational::from(3)));
+        assert_eq!(Rational::from_f64(0.0), Some(Rational::from(0)));
+        assert_eq!(Rational::from_f64(-0.0), Some(Rational::from(0)));
+
+        // 0.1 can't be represented exactly, so the stored value is the closest fraction over a power of two
+        let tenth = Rational::from_f64(0.1).unwrap();
+        assert_ne!(tenth, Rational::new(1, 10));
+        assert_eq!(tenth, Rational::new(3602879701896397, 1 << 55));
+        assert_eq!(tenth.to_f64(), 0.1);
+
+        assert_eq!(
+            Rational::from_f64(1e30),
+            Some(Rational::from(
+                1_000_000_000_000_000_019_884_624_838_656i128
+            ))
+        );
+
+        assert_eq!(Rational::from_f64(f64::NAN), None);
+        assert_eq!(Rational::from_f64(f64::INFINITY), None);
+        assert_eq!(Rational::from_f64(f64::NEG_INFINITY), None);
+
+        // The whole exponent range is covered, including subnormals
+        assert_eq!(
+            Rational::from_f64(f64::MIN_POSITIVE),
+            Some(Rational::from_bigints(
+                BigInt::from(1),
+                BigInt::from(2).pow(1022)
+            ))
+        );
+        assert_eq!(
+            Rational::from_f64(f64::from_bits(1)),
+            Some(Rational::from_bigints(
+                BigInt::from(1),
+                BigInt::from(2).pow(1074)
+            ))
+        );
+        assert_eq!(
+            Rational::from_f64(-f64::MAX),
+            Some(Rational::from(
+                -(BigInt::from(2).pow(53) - BigInt::from(1)) * BigInt::from(2).pow(971)
+            ))
+        );
+
+        for value in [
+            1e300,
+            -1e300,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+            -1.5e-310,
+            1e-300,
+        ] {
+            assert_eq!(Rational::from_f64(value).unwrap().to_f64(), value);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn approximate() {
+        assert_eq!(Rational::approximate(0.3333, 10), Rational::new(1, 3));
+        assert_eq!(
+            Rational::approximate(core::f64::consts::PI, 10),
+            Rational::new(22, 7)
+        );
+        assert_eq!(
+            Rational::approximate(core::f64::consts::PI, 100),
+            Rational::new(311, 99)
+        );
+        assert_eq!(
+            Rational::approximate(core::f64::consts::PI, 1000),
+            Rational::new(355, 113)
+        );
+        assert_eq!(Rational::approximate(-0.75, 100), Rational::new(-3, 4));
+        assert_eq!(Rational::approximate(2.0, 1), Rational::from(2));
+        assert_eq!(Rational::approximate(0.6, 1), Rational::from(1));
+
+        // The convergents overflow long before reaching the maximum denominator
+        let approximation = Rational::approximate(0.1234567, i128::MAX);
+        assert!((approximation.to_f64() - 0.1234567).abs() < 1e-15);
+
+        // Huge floats are integers, even if they don't fit into an `i128`
+        assert_eq!(
+            Rational::approximate(1e300, 10),
+            Rational::from_f64(1e300).unwrap()
+        );
+        assert_eq!(
+            Rational::approximate(-1e20, 10),
+            Rational::from(-100000000000000000000i128)
+        );
+    }
+
+    #[test]
+    fn promotion_past_i128() {
+        let max = Rational::from(i128::MAX);
+        let tiny = Rational::new(1, i128::MAX);
+
+        assert_eq!(
+            &max + &Rational::from(1),
+            Rational::from(BigInt::from(i128::MAX) + BigInt::from(1))
+        );
+        assert_eq!(
+            Rational::from(i128::MIN) - Rational::from(1),
+            Rational::from(BigInt::from(i128::MIN) - BigInt::from(1))
+        );
+        assert_eq!(
+            &max * &Rational::from(2),
+            Rational::from(BigInt::from(i128::MAX) * BigInt::from(2))
+        );
+        assert_eq!(
+            &tiny / &max,
+            Rational::from_bigints(BigInt::from(1), BigInt::from(i128::MAX).pow(2))
+        );
+        assert_eq!(
+            -Rational::from(i128::MIN),
+            Rational::from(-BigInt::from(i128::MIN))
+        );
+
+        // Results which fit again are stored inline
+        assert_eq!((&max + &Rational::from(1)) - Rational::from(1), max);
+        assert_eq!(((&max * &max) / &max).to_i128_parts(), Some((i128::MAX, 1)));
+        assert_eq!(
+            Rational::new(1, 2).checked_div(&Rational::new(2, 3)),
+            Some(Rational::new(3, 4))
+        );
+    }
+
+    #[test]
+    fn repeated_addition_past_i128() {
+        // The sum's numerator exceeds `i128::MAX` after a few steps, and the cross products of the terms overflow
+        // (~2^125 * ~2^63) even before that
+        let numer = (1 << 125) + 3;
+        let denom = 3i128.pow(40);
+        let term = Rational::new(numer, denom);
+
+        let mut sum = Rational::from(0);
+        let mut reference = BigInt::zero();
+
+        for step in 1..=20 {
+            sum += &term;
+            reference = reference + BigInt::from(numer);
+
+            let gcd = reference.gcd(&BigInt::from(denom));
+
+            assert_eq!(
+                sum.numer(),
+                &reference / &gcd,
+                "numerator after {} steps",
+                step
+            );
+            assert_eq!(
+                sum.denom(),
+                &BigInt::from(denom) / &gcd,
+                "denominator after {} steps",
+                step
+            );
+        }
+
+        assert!(sum.to_i128_parts().is_none());
+        assert_eq!(&term * &Rational::from(20), sum);
+        assert!(sum > term);
+        assert!(-&sum < term);
+        assert_eq!(
+            format!("{}", sum),
+            format!("{}/{}", sum.numer(), sum.denom())
+        );
+    }
+
     #[test]
     fn equality() {
         assert_eq!(Rational::new(1, 2), Rational::new(1, 2));
@@ -352,10 +1407,15 @@ mod tests {
         assert_eq!(Rational::new(1, 2), Rational::new(-2, -4));
         assert_ne!(Rational::new(1, 2), Rational::new(-2, 4));
         assert_ne!(Rational::new(1, 2), Rational::new(2, -4));
-        assert_ne!(
-            Rational { numer: 6, denom: 2 },
-            Rational { numer: 7, denom: 2 }
-        );
+        assert_ne!(Rational::new(6, 2), Rational::new(7, 2));
+
+        // Values are compared part by part without any arithmetic, so even parts whose cross products would overflow
+        // are fine
+        let huge = Rational::new(i128::MAX, i128::MAX - 1);
+        assert_eq!(huge, Rational::new(i128::MAX, i128::MAX - 1));
+        assert_ne!(huge, Rational::new(i128::MAX - 2, i128::MAX - 1));
+        assert_ne!(huge, huge.reciprocal());
+        assert_eq!(huge.reciprocal().reciprocal(), huge);
     }
 
     #[test]
@@ -364,4 +1424,128 @@ mod tests {
         assert!(Rational::new(2, 3) > Rational::new(1, 2));
         assert!(Rational::new(-2, 3) < Rational::new(1, 2));
     }
+
+    #[test]
+    fn ordering_with_negative_denominators() {
+        let minus_half = Rational::new(1, -2);
+
+        assert!(minus_half < Rational::new(1, 4));
+        assert!(minus_half > -1);
+        assert_eq!(minus_half.cmp(&Rational::new(-1, 2)), Ordering::Equal);
+        assert!(Rational::new(-3, -4) > Rational::new(1, 2));
+        assert!(Rational::new(1, 4) > minus_half);
+
+        // -1/2 flipped over is -2
+        assert!(Rational::new(-1, 2).reciprocal() < 0);
+        assert!(Rational::new(-1, 2).reciprocal() < Rational::new(-3, 2));
+    }
+
+    #[test]
+    fn integer_comparison() {
+        assert!(Rational::new(1, 2) > 0);
+        assert!(Rational::new(-1, 2) < 0);
+        assert!(Rational::new(7, 2) < 4);
+        assert!(Rational::new(8, 2) <= 4);
+        assert!(Rational::new(3, 1) == 3);
+        assert!(Rational::new(6, 2) == 3);
+        assert!(Rational::new(1, 3) != 0);
+    }
+
+    #[test]
+    fn rpow() {
+        assert_eq!(
+            Rational::new(4, 9).rpow(Rational::new(1, 2)),
+            Some(Rational::new(2, 3))
+        );
+        assert_eq!(
+            Rational::new(4, 9).rpow(Rational::new(3, 2)),
+            Some(Rational::new(8, 27))
+        );
+        assert_eq!(
+            Rational::new(4, 9).rpow(Rational::new(-1, 2)),
+            Some(Rational::new(3, 2))
+        );
+        assert_eq!(
+            Rational::from(-27).rpow(Rational::new(2, 3)),
+            Some(Rational::from(9))
+        );
+        assert_eq!(
+            Rational::from(3).rpow(Rational::from(4)),
+            Some(Rational::from(81))
+        );
+        assert_eq!(
+            Rational::new(5, 7).rpow(Rational::from(0)),
+            Some(Rational::from(1))
+        );
+        assert_eq!(
+            Rational::from(0).rpow(Rational::new(1, 3)),
+            Some(Rational::from(0))
+        );
+
+        assert_eq!(Rational::from(2).rpow(Rational::new(1, 2)), None);
+        assert_eq!(Rational::from(-4).rpow(Rational::new(1, 2)), None);
+        assert_eq!(Rational::from(0).rpow(Rational::from(-1)), None);
+        assert_eq!(
+            Rational::from(10).rpow(Rational::from(100)),
+            Some(Rational::from(BigInt::from(10).pow(100)))
+        );
+
+        // Roots of values beyond 64 bits go through big integers
+        let big = Rational::new(1 << 100, 3).pow(6);
+        assert_eq!(
+            big.rpow(Rational::new(5, 3)),
+            Some(Rational::new(1 << 100, 3).pow(10))
+        );
+        assert_eq!(
+            (-big.clone()).rpow(Rational::new(1, 3)),
+            Some(-Rational::new(1 << 100, 3).pow(2))
+        );
+        assert_eq!((-big.clone()).rpow(Rational::new(1, 2)), None);
+        assert_eq!((big + Rational::from(1)).rpow(Rational::new(1, 2)), None);
+
+        // The first power is the value itself, even if it's way beyond 64 bits
+        for value in [10i128.pow(20), 1 << 70, i128::MAX, i128::MIN, -(1 << 100)] {
+            assert_eq!(
+                Rational::from(value).rpow(Rational::from(1)),
+                Some(Rational::from(value))
+            );
+        }
+
+        assert_eq!(integer_nth_root(i128::MIN, 127), Some(-2));
+        assert_eq!(integer_nth_root(1 << 126, 2), Some(1 << 63));
+        assert_eq!(integer_nth_root(i128::MAX, 2), None);
+    }
+
+    #[test]
+    fn latex() {
+        assert_eq!(Rational::from(-3).to_latex(), "-3");
+        assert_eq!(Rational::new(6, 4).to_latex(), "\\frac{3}{2}");
+        assert_eq!(Rational::new(1, -2).to_latex(), "-\\frac{1}{2}");
+    }
+
+    #[test]
+    fn mixed_numbers() {
+        assert_eq!(
+            Rational::new(7, 3).to_mixed(),
+            (BigInt::from(2), Rational::new(1, 3))
+        );
+        assert_eq!(
+            Rational::new(-7, 3).to_mixed(),
+            (BigInt::from(-2), Rational::new(-1, 3))
+        );
+        assert_eq!(
+            Rational::new(6, 2).to_mixed(),
+            (BigInt::from(3), Rational::from(0))
+        );
+        assert_eq!(
+            Rational::new(1, -3).to_mixed(),
+            (BigInt::zero(), Rational::new(-1, 3))
+        );
+
+        assert_eq!(format!("{:#}", Rational::new(7, 3)), "2 1/3");
+        assert_eq!(format!("{:#}", Rational::new(-7, 3)), "-2 1/3");
+        assert_eq!(format!("{:#}", Rational::from(-4)), "-4");
+        assert_eq!(format!("{:#}", Rational::new(-1, 3)), "-1/3");
+        assert_eq!(format!("{}", Rational::new(7, 3)), "7/3");
+    }
 }
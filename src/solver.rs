@@ -1,29 +1,365 @@
+use crate::bigint::BigInt;
 use crate::polynomial::Polynomial;
 use crate::rational::Rational;
-use std::cmp::Ordering;
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{cmp::Ordering, fmt::Display, iter};
 
-pub fn solve_univariate_polynomial(poly: &Polynomial) -> Vec<Rational> {
-    match poly.degree() {
-        1 => vec![-poly.get(0) / poly.get(1)],
-        2 => {
-            let a = poly.get(2);
-            let b = poly.get(1);
-            let c = poly.get(0);
-
-            let discriminant = b * b - Rational::from(4) * a * c;
-
-            match discriminant.cmp(&Rational::from(0)) {
-                Ordering::Greater => {
-                    vec![
-                        (-b - discriminant.sqrt()) / (Rational::from(2) * a),
-                        (-b + discriminant.sqrt()) / (Rational::from(2) * a),
-                    ]
+/// A root of a polynomial along with the number of times it's repeated.
+///
+/// The value is rational by default, while [`find_roots`] gives values tagged as a [`RootValue`], which aren't
+/// necessarily rational.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Root<T = Rational> {
+    pub value: T,
+    pub multiplicity: u32,
+}
+
+/// The value of a root, tagged by how it was found.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RootValue {
+    /// A rational root, which is always found exactly.
+    Exact(Rational),
+    /// An irrational real root `rational + coeff * sqrt(radicand)` of a quadratic factor, where `radicand` is a positive
+    /// integer that isn't a perfect square.
+    Surd {
+        rational: Rational,
+        coeff: Rational,
+        radicand: i128,
+    },
+    /// A non-real root `real + imag * i * sqrt(radicand)` of a quadratic factor, where `radicand` is a positive integer
+    /// (1 if the imaginary part is rational).
+    Complex {
+        real: Rational,
+        imag: Rational,
+        radicand: i128,
+    },
+    /// A real root which could only be approximated numerically.
+    Approx(f64),
+}
+
+impl RootValue {
+    /// Check whether the value is known exactly rather than just approximated.
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, RootValue::Approx(_))
+    }
+
+    /// Get the value as a float, or `None` if it isn't real.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            RootValue::Exact(value) => Some(value.to_f64()),
+            RootValue::Surd {
+                rational,
+                coeff,
+                radicand,
+            } => {
+                // Newton's method, as `core` doesn't provide `f64::sqrt`. Starting above the root, it decreases
+                // monotonically until it can't get any closer.
+                let radicand = *radicand as f64;
+                let mut sqrt = radicand;
+
+                loop {
+                    let next = (sqrt + radicand / sqrt) / 2.0;
+
+                    if next >= sqrt {
+                        break;
+                    }
+                    sqrt = next;
                 }
-                Ordering::Equal => vec![-b / (Rational::from(2) * a)],
-                Ordering::Less => vec![],
+
+                Some(rational.to_f64() + coeff.to_f64() * sqrt)
+            }
+            RootValue::Complex { .. } => None,
+            RootValue::Approx(value) => Some(*value),
+        }
+    }
+
+    /// Get the numerator parts `(p, q)` and denominator `d` of a surd or complex value written over a common
+    /// denominator, i.e. `(p + q sqrt(radicand))/d` for a surd. The rational and irrational parts are both kept in
+    /// lowest terms, so `p`, `q` and `d` never share a common factor, e.g. a root `(2 + 2√3)/4` is always given as
+    /// `(1 + √3)/2`.
+    ///
+    /// Returns `None` for rational and approximate values, or if the common denominator is too large.
+    pub fn as_fraction(&self) -> Option<(i128, i128, i128)> {
+        let (rational, coeff) = match self {
+            RootValue::Surd {
+                rational, coeff, ..
+            } => (rational, coeff),
+            RootValue::Complex { real, imag, .. } => (real, imag),
+            RootValue::Exact(_) | RootValue::Approx(_) => return None,
+        };
+        let (rational_numer, rational_denom) = rational.to_i128_parts()?;
+        let (coeff_numer, coeff_denom) = coeff.to_i128_parts()?;
+
+        // lcm(a, b) = a * b/gcd(a, b), and b/gcd(a, b) is the denominator of a/b in lowest terms
+        let denom = rational_denom.checked_mul(
+            Rational::new(rational_denom, coeff_denom)
+                .to_i128_parts()?
+                .1,
+        )?;
+
+        Some((
+            rational_numer.checked_mul(denom / rational_denom)?,
+            coeff_numer.checked_mul(denom / coeff_denom)?,
+            denom,
+        ))
+    }
+
+    /// Format as LaTeX, e.g. `\frac{1 - 3\sqrt{5}}{2}` or `-1 + i\sqrt{3}`.
+    pub fn to_latex(&self) -> String {
+        let mut latex = String::new();
+        self.write(&mut latex, true, None)
+            .expect("writing to a string can't fail");
+
+        latex
+    }
+
+    /// Write the value either in plain text like `(1 - 3√5)/2` or as LaTeX like `\frac{1 - 3\sqrt{5}}{2}`.
+    /// Approximate values are written with `precision` decimal places if it's given.
+    fn write(
+        &self,
+        out: &mut dyn core::fmt::Write,
+        latex: bool,
+        precision: Option<usize>,
+    ) -> core::fmt::Result {
+        let sqrt = |radicand: i128| {
+            if latex {
+                format!("\\sqrt{{{}}}", radicand)
+            } else {
+                format!("√{}", radicand)
             }
+        };
+
+        let (rational, coeff, unit) = match self {
+            RootValue::Exact(value) if latex => return write!(out, "{}", value.to_latex()),
+            RootValue::Exact(value) => return write!(out, "{}", value),
+            RootValue::Approx(value) => {
+                let approx = if latex { "\\approx" } else { "≈" };
+
+                return match precision {
+                    Some(precision) => write!(out, "{} {:.*}", approx, precision, value),
+                    None => write!(out, "{} {}", approx, value),
+                };
+            }
+            RootValue::Surd {
+                rational,
+                coeff,
+                radicand,
+            } => (rational, coeff, sqrt(*radicand)),
+            RootValue::Complex {
+                real,
+                imag,
+                radicand: 1,
+            } => (real, imag, String::from("i")),
+            RootValue::Complex {
+                real,
+                imag,
+                radicand,
+            } => (real, imag, format!("i{}", sqrt(*radicand))),
+        };
+
+        let sign = if coeff.is_negative() { '-' } else { '+' };
+
+        // Write `|coeff| unit` as `n unit/d`, leaving out the numerator and denominator if they're 1
+        let write_scaled = |out: &mut dyn core::fmt::Write, coeff: &Rational| {
+            let coeff = coeff.abs();
+            let numer = if coeff.numer() == BigInt::from(1) {
+                unit.clone()
+            } else {
+                format!("{}{}", coeff.numer(), unit)
+            };
+
+            match coeff.denom() {
+                denom if denom == BigInt::from(1) => write!(out, "{}", numer),
+                denom if latex => write!(out, "\\frac{{{}}}{{{}}}", numer, denom),
+                denom => write!(out, "{}/{}", numer, denom),
+            }
+        };
+
+        // Put both parts over a common denominator if they need one
+        if let Some((p, q, d)) = self.as_fraction().filter(|&(p, _, d)| p != 0 && d != 1) {
+            write!(
+                out,
+                "{}{} {} ",
+                if latex { "\\frac{" } else { "(" },
+                p,
+                sign
+            )?;
+            write_scaled(out, &Rational::from(q))?;
+            return if latex {
+                write!(out, "}}{{{}}}", d)
+            } else {
+                write!(out, ")/{}", d)
+            };
+        }
+
+        if !rational.is_zero() {
+            if latex {
+                write!(out, "{} {} ", rational.to_latex(), sign)?;
+            } else {
+                write!(out, "{} {} ", rational, sign)?;
+            }
+        } else if coeff.is_negative() {
+            write!(out, "-")?;
+        }
+
+        write_scaled(out, coeff)
+    }
+}
+
+impl Display for RootValue {
+    /// Display surds like `(1 - 3√5)/2` and complex values like `-1 + i√3`, while approximate values are marked with
+    /// `≈` and respect the precision of the formatter, e.g. `≈ 2.094` for `{:.3}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let precision = f.precision();
+        self.write(f, false, precision)
+    }
+}
+
+/// A step taken while solving an equation, for showing how its roots were found.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceStep {
+    /// The equation with everything moved to the left-hand side.
+    Normalized(Polynomial),
+    /// The candidates for rational roots given by the rational root theorem, which are all tested.
+    Candidates(Vec<Rational>),
+    /// A candidate turned out to be a root, repeated `multiplicity` times.
+    Root { value: Rational, multiplicity: u32 },
+    /// The polynomial left over after dividing out the linear factors of the last root.
+    Deflated(Polynomial),
+    /// The roots of the left over polynomial which aren't rational.
+    Irrational(Vec<RootValue>),
+}
+
+impl Display for TraceStep {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let join = |values: Vec<String>| values.join(", ");
+
+        match self {
+            TraceStep::Normalized(poly) => write!(f, "solve {} = 0", poly),
+            TraceStep::Candidates(candidates) => write!(
+                f,
+                "try the rational candidates {{{}}}",
+                join(candidates.iter().map(|value| value.to_string()).collect())
+            ),
+            TraceStep::Root {
+                value,
+                multiplicity: 1,
+            } => write!(f, "found the root {}", value),
+            TraceStep::Root {
+                value,
+                multiplicity,
+            } => write!(f, "found the root {} (×{})", value, multiplicity),
+            TraceStep::Deflated(poly) => write!(f, "divide it out, leaving {}", poly),
+            TraceStep::Irrational(values) => write!(
+                f,
+                "solve the rest for {{{}}}",
+                join(values.iter().map(|value| format!("{:.3}", value)).collect())
+            ),
         }
-        _ => {
+    }
+}
+
+/// An open interval of real numbers, where a missing bound means that it's unbounded in that direction. The bounds are
+/// roots of a polynomial, so they may be irrational.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interval {
+    pub lower: Option<RootValue>,
+    pub upper: Option<RootValue>,
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match &self.lower {
+            Some(lower) => write!(f, "({}, ", lower)?,
+            None => write!(f, "(-inf, ")?,
+        }
+
+        match &self.upper {
+            Some(upper) => write!(f, "{})", upper),
+            None => write!(f, "inf)"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolverError {
+    /// The rational root theorem requires integer coefficients.
+    NonIntegerCoefficients,
+    /// The constant or leading coefficient is too large to be factored for the rational root theorem.
+    CoefficientsTooLarge,
+    /// The roots can't be represented exactly as rationals.
+    IrrationalRoots,
+    /// The polynomial has a factor of the given degree which has no rational roots and can't be solved analytically.
+    UnsupportedDegree(u32),
+}
+
+impl Display for SolverError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            SolverError::NonIntegerCoefficients => {
+                write!(
+                    f,
+                    "polynomials with non-integer coefficients are not supported yet"
+                )
+            }
+            SolverError::CoefficientsTooLarge => {
+                write!(f, "coefficients are too large to be factored")
+            }
+            SolverError::IrrationalRoots => write!(f, "irrational roots are not supported yet"),
+            SolverError::UnsupportedDegree(degree) => {
+                write!(f, "{}th degree polynomials are not supported yet", degree)
+            }
+        }
+    }
+}
+
+impl core::error::Error for SolverError {}
+
+/// Find all real roots of `poly`.
+pub fn solve_univariate_polynomial(poly: &Polynomial) -> Result<Vec<Root>, SolverError> {
+    if let Some((a, b)) = poly.as_linear() {
+        return Ok(vec![Root {
+            value: -b / a,
+            multiplicity: 1,
+        }]);
+    }
+
+    match poly.as_quadratic() {
+        Some((a, b, _)) => {
+            let discriminant = poly.discriminant();
+
+            match discriminant.signum() {
+                1 => {
+                    let discriminant_sqrt = discriminant
+                        .checked_sqrt()
+                        .ok_or(SolverError::IrrationalRoots)?;
+
+                    Ok(vec![
+                        Root {
+                            value: (-&b - &discriminant_sqrt) / (Rational::from(2) * &a),
+                            multiplicity: 1,
+                        },
+                        Root {
+                            value: (-b + discriminant_sqrt) / (Rational::from(2) * a),
+                            multiplicity: 1,
+                        },
+                    ])
+                }
+                // A zero discriminant means the polynomial is a perfect square, so its only root is a double root
+                0 => Ok(vec![Root {
+                    value: -b / (Rational::from(2) * a),
+                    multiplicity: 2,
+                }]),
+                _ => Ok(vec![]),
+            }
+        }
+        None => {
             /* Algorithm:
             let P be the polynomial of degree deg(P)
 
@@ -36,57 +372,1418 @@ pub fn solve_univariate_polynomial(poly: &Polynomial) -> Vec<Rational> {
                 5. end
             */
 
-            let mut roots = Vec::new();
-
-            // Find all the rational roots using the rational root theorem (https://en.wikipedia.org/wiki/Rational_root_theorem)
             // TODO: normalization of non-integer coefficients
-            let ps = integer_factors(
-                poly.get(0)
-                    .as_integer()
-                    .expect("todo: normalization of non-integer coefficients"),
-            );
-            let qs = integer_factors(
-                poly.get(poly.degree())
-                    .as_integer()
-                    .expect("todo: normalization of non-integer coefficients"),
-            );
+            let rational_roots = rational_roots(poly).ok_or_else(|| {
+                if poly.terms().all(|(_, coeff)| coeff.is_integer()) {
+                    SolverError::CoefficientsTooLarge
+                } else {
+                    SolverError::NonIntegerCoefficients
+                }
+            })?;
+
+            let mut roots: Vec<Root> = Vec::new();
+            let mut remaining = poly.clone();
+
+            for value in rational_roots {
+                remaining = remaining.synthetic_div(value.clone()).0;
+
+                // Roots are repeated according to their multiplicity, so equal roots are always adjacent
+                match roots.last_mut() {
+                    Some(root) if root.value == value => root.multiplicity += 1,
+                    _ => roots.push(Root {
+                        value,
+                        multiplicity: 1,
+                    }),
+                }
+            }
+
+            // Whatever is left over has no rational roots, which is only solvable if it's at most quadratic
+            match remaining.degree() {
+                0 => (),
+                2 => roots.append(&mut solve_univariate_polynomial(&remaining)?),
+                degree => return Err(SolverError::UnsupportedDegree(degree)),
+            }
+
+            Ok(roots)
+        }
+    }
+}
 
-            for &p in &ps {
-                for &q in &qs {
-                    let potential_root = Rational::new(p, q);
+/// Find all roots of `poly`, even the ones that can't be represented as rationals.
+///
+/// Rational roots come first, followed by the roots of whatever is left over, which is split into square-free factors
+/// so that repeated roots keep their multiplicity. Quadratic factors (including any with rational coefficients that can
+/// be split off a larger factor) give exact surds or complex conjugates, and otherwise the real roots are approximated
+/// numerically. Non-real roots of anything but a quadratic factor aren't found at all.
+pub fn find_roots(poly: &Polynomial) -> Vec<Root<RootValue>> {
+    find_roots_with_trace(poly, &mut Vec::new())
+}
+
+/// Like [`find_roots`], but records the steps taken to find the roots in `trace`.
+pub fn find_roots_with_trace(
+    poly: &Polynomial,
+    trace: &mut Vec<TraceStep>,
+) -> Vec<Root<RootValue>> {
+    if poly.degree() == 0 {
+        return vec![];
+    }
+
+    let mut roots: Vec<Root<RootValue>> = Vec::new();
+    // The primitive part always has integer coefficients, so the rational root theorem always applies
+    let mut remaining = poly.primitive_part();
+
+    let rational_roots = rational_roots_with_trace(&remaining, trace).unwrap_or_default();
+
+    // Roots are repeated according to their multiplicity, so equal roots are always adjacent
+    for repeated in rational_roots.chunk_by(|a, b| a == b) {
+        let value = repeated[0].clone();
+        let multiplicity = repeated.len() as u32;
+
+        for _ in 0..multiplicity {
+            remaining = remaining.synthetic_div(value.clone()).0;
+        }
+
+        roots.push(Root {
+            value: RootValue::Exact(value.clone()),
+            multiplicity,
+        });
+        trace.push(TraceStep::Root {
+            value,
+            multiplicity,
+        });
+        trace.push(TraceStep::Deflated(remaining.clone()));
+    }
+
+    // Every root of a square-free factor has the factor's multiplicity, which numerical methods couldn't tell
+    for (factor, multiplicity) in remaining.squarefree_decomposition() {
+        let irrational_roots = irrational_roots(&factor);
+
+        if !irrational_roots.is_empty() {
+            trace.push(TraceStep::Irrational(irrational_roots.clone()));
+        }
+
+        roots.extend(irrational_roots.into_iter().map(|value| Root {
+            value,
+            multiplicity,
+        }));
+    }
+
+    roots
+}
+
+/// Find the roots of a square-free polynomial without rational roots, exactly for its quadratic factors and
+/// numerically otherwise.
+fn irrational_roots(poly: &Polynomial) -> Vec<RootValue> {
+    let approximate = |poly: &Polynomial| {
+        real_roots_f64(poly)
+            .into_iter()
+            .map(RootValue::Approx)
+            .collect::<Vec<_>>()
+    };
+
+    let (mut quadratics, rest) = split_quadratic_factors(poly);
+    let mut roots = Vec::new();
+
+    // Whatever is left over might be quadratic itself, or even linear if its coefficients were too large to look for
+    // rational roots
+    let approximated = match rest.degree() {
+        0 => vec![],
+        1 => {
+            let (a, b) = rest.as_linear().unwrap();
+            vec![RootValue::Exact(-b / a)]
+        }
+        2 => {
+            quadratics.push(rest);
+            vec![]
+        }
+        _ => approximate(&rest),
+    };
+
+    for quadratic in &quadratics {
+        roots.extend(quadratic_surd_roots(quadratic).unwrap_or_else(|| approximate(quadratic)));
+    }
 
-                    // Check if it's an actual root
-                    if poly.eval(potential_root) == Rational::from(0) {
-                        // If so, determine the multiplicity by counting the number of derivatives that vanish (are 0) at the root
-                        let mut test_derivative = poly.diff();
-                        let mut multiplicity = 1;
+    roots.extend(approximated);
+    roots
+}
+
+/// Split quadratic factors with rational coefficients off a polynomial without rational roots, like `x^2 - 2` and
+/// `x^2 - 3` off `x^4 - 5x^2 + 6`, returning them along with whatever is left over.
+///
+/// Only factors with real roots can be found: their roots are approximated numerically, and the coefficients of every
+/// pair of them are rounded and then checked by dividing exactly. By Gauss's lemma, a factor of a polynomial with
+/// integer coefficients can be scaled to have integer coefficients, with a leading coefficient dividing the
+/// polynomial's.
+fn split_quadratic_factors(poly: &Polynomial) -> (Vec<Polynomial>, Polynomial) {
+    // Rounds half away from zero, as `core` doesn't provide `f64::round`
+    let round = |value: f64| Rational::from((value + 0.5f64.copysign(value)) as i128);
 
-                        while test_derivative.eval(potential_root) == Rational::from(0) {
-                            multiplicity += 1;
-                            test_derivative = test_derivative.diff();
-                        }
+    let mut factors = Vec::new();
+    let mut remaining = poly.primitive_part();
 
-                        roots.append(&mut [potential_root].repeat(multiplicity));
+    'search: while remaining.degree() > 2 {
+        let Some(scales) = remaining
+            .get(remaining.degree())
+            .as_integer()
+            .and_then(|leading| integer_factors(leading.abs()))
+        else {
+            break;
+        };
+        let roots = real_roots_f64(&remaining);
+
+        for (i, &a) in roots.iter().enumerate() {
+            for &b in &roots[i + 1..] {
+                for &scale in &scales {
+                    let scale_f64 = scale as f64;
+
+                    // scale (x - a)(x - b) = scale x^2 - scale (a + b) x + scale ab
+                    let candidate = Polynomial::from(vec![
+                        (0, round(scale_f64 * a * b)),
+                        (1, round(-scale_f64 * (a + b))),
+                        (2, Rational::from(scale)),
+                    ]);
+
+                    if candidate.divides(&remaining) {
+                        remaining = remaining.quotient(&candidate).primitive_part();
+                        factors.push(candidate);
+                        continue 'search;
                     }
                 }
             }
+        }
 
-            roots
+        break;
+    }
+
+    (factors, remaining)
+}
+
+/// Find the two roots `-b/2a ± sqrt(D)/2a` of a quadratic exactly, as surds if the discriminant `D` is positive and as
+/// complex conjugates if it's negative.
+///
+/// Returns `None` if the discriminant is too large to be simplified.
+fn quadratic_surd_roots(poly: &Polynomial) -> Option<Vec<RootValue>> {
+    let (a, b, _) = poly.as_quadratic()?;
+    let discriminant = poly.discriminant();
+
+    // sqrt(n/d) = sqrt(nd)/d, so the radicand can be made an integer
+    let (numer, denom) = discriminant.to_i128_parts()?;
+    let (square, radicand) = split_square_factors(numer.checked_mul(denom)?.abs());
+
+    let center = -b / (Rational::from(2) * &a);
+    let offset = (Rational::from(square) / Rational::from(denom) / (Rational::from(2) * a)).abs();
+
+    // There are no rational roots, so the discriminant is neither 0 nor a perfect square
+    Some(if discriminant.is_positive() {
+        vec![
+            RootValue::Surd {
+                rational: center.clone(),
+                coeff: -&offset,
+                radicand,
+            },
+            RootValue::Surd {
+                rational: center,
+                coeff: offset,
+                radicand,
+            },
+        ]
+    } else {
+        vec![
+            RootValue::Complex {
+                real: center.clone(),
+                imag: -&offset,
+                radicand,
+            },
+            RootValue::Complex {
+                real: center,
+                imag: offset,
+                radicand,
+            },
+        ]
+    })
+}
+
+/// Find the roots of a product of polynomial factors with their exponents, like `x^2 (x - 1)`, by finding the roots of
+/// each factor separately. Linear factors are solved directly, so this is cheaper than expanding the product first.
+///
+/// The roots are given in the order of the factors, and roots shared between factors are only listed once, with their
+/// multiplicities added up.
+pub fn find_roots_of_product(factors: &[(Polynomial, u32)]) -> Vec<Root<RootValue>> {
+    let mut roots: Vec<Root<RootValue>> = Vec::new();
+
+    for (factor, exponent) in factors {
+        let factor_roots = match factor.as_linear() {
+            Some((a, b)) => vec![Root {
+                value: RootValue::Exact(-b / a),
+                multiplicity: 1,
+            }],
+            None => find_roots(factor),
+        };
+
+        for root in factor_roots {
+            let multiplicity = root.multiplicity * exponent;
+
+            match roots
+                .iter_mut()
+                .find(|existing| existing.value == root.value)
+            {
+                Some(existing) => existing.multiplicity += multiplicity,
+                None => roots.push(Root {
+                    value: root.value,
+                    multiplicity,
+                }),
+            }
         }
     }
+
+    roots
 }
 
-fn integer_factors(n: i64) -> Vec<i64> {
-    let mut factors = Vec::new();
+/// Split a positive integer `n` into `(s, r)` such that `n = s^2 r`, pulling as many square factors into `s` as
+/// possible, e.g. `sqrt(72) = 6 sqrt(2)`.
+fn split_square_factors(mut n: i128) -> (i128, i128) {
+    // Trial division gets too slow for huge `n`, but a few leftover square factors still give a valid (if unsimplified)
+    // radicand
+    const MAX_FACTOR: i128 = 1_000_000;
+
+    let mut square = 1;
+    let mut factor = 2;
+
+    while factor <= MAX_FACTOR && factor * factor <= n {
+        while n % (factor * factor) == 0 {
+            n /= factor * factor;
+            square *= factor;
+        }
+
+        factor += 1;
+    }
+
+    // What's left over might itself be a perfect square of a large factor
+    if let Some(root) = Rational::from(n).checked_sqrt() {
+        return (square * root.as_integer().unwrap(), 1);
+    }
+
+    (square, n)
+}
+
+/// Find all `x` for which `poly(x)` compares to 0 as `ordering`, i.e. `Ordering::Greater` solves `poly(x) > 0`. The
+/// solution is returned as a union of disjoint open intervals in ascending order.
+///
+/// The sign of a polynomial can only change at its real roots, which are found by [`find_roots`] (even irrational
+/// ones), and it does so exactly at those of odd multiplicity. `Ordering::Equal` isn't an inequality and just gives an
+/// empty set.
+pub fn solve_inequality(poly: &Polynomial, ordering: Ordering) -> Vec<Interval> {
+    let mut roots = find_roots(poly)
+        .into_iter()
+        .filter_map(|root| Some((root.value.to_f64()?, root)))
+        .collect::<Vec<_>>();
+    roots.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let mut intervals = Vec::new();
+    let mut lower = None;
+    let mut sign = poly.sign_at_neg_infinity();
+
+    // The sign is the same throughout each interval between consecutive roots
+    for (_, root) in roots {
+        if ordering != Ordering::Equal && sign.cmp(&0) == ordering {
+            intervals.push(Interval {
+                lower,
+                upper: Some(root.value.clone()),
+            });
+        }
+
+        if root.multiplicity % 2 == 1 {
+            sign = -sign;
+        }
+        lower = Some(root.value);
+    }
+
+    if ordering != Ordering::Equal && sign.cmp(&0) == ordering {
+        intervals.push(Interval { lower, upper: None });
+    }
+
+    intervals
+}
+
+/// Find just the rational roots of `poly` along with their multiplicities, in the order the rational root theorem finds
+/// them. Unlike [`find_roots`], this never goes on to look for irrational or complex roots, so any factors without
+/// rational roots are simply ignored.
+pub fn solve_rational_roots_only(poly: &Polynomial) -> Vec<Root> {
+    // The primitive part always has integer coefficients, so the rational root theorem always applies
+    rational_roots(&poly.primitive_part())
+        .unwrap_or_default()
+        .chunk_by(|a, b| a == b)
+        .map(|repeated| Root {
+            value: repeated[0].clone(),
+            multiplicity: repeated.len() as u32,
+        })
+        .collect()
+}
+
+/// Find all rational roots of `poly`, each repeated according to its multiplicity.
+///
+/// Returns `None` if `poly` has non-integer coefficients, as the rational root theorem doesn't apply to them, or if its
+/// (non-zero) constant or leading coefficient can't be factored, which is only the case for some beyond 10^12. Constant
+/// polynomials (including the zero polynomial) have no roots to report.
+pub fn rational_roots(poly: &Polynomial) -> Option<Vec<Rational>> {
+    rational_roots_with_trace(poly, &mut Vec::new())
+}
+
+/// Like [`rational_roots`], but records the candidates given by the rational root theorem in `trace`.
+fn rational_roots_with_trace(
+    poly: &Polynomial,
+    trace: &mut Vec<TraceStep>,
+) -> Option<Vec<Rational>> {
+    // Constant polynomials have no roots, except for the zero polynomial whose roots can't be enumerated
+    if poly.degree() == 0 {
+        return Some(vec![]);
+    }
+
+    // If the lowest k coefficients are zero, then 0 is a root of multiplicity k and x^k can be factored out. This has to
+    // be done up front because the rational root theorem can't find 0 as a root (it has no divisors).
+    let zero_multiplicity = (0..poly.degree())
+        .take_while(|&degree| poly.get(degree) == 0)
+        .count();
+
+    let mut roots = vec![Rational::from(0); zero_multiplicity];
+    let poly = &poly
+        .div_rem(&Polynomial::new(BTreeMap::from([(
+            zero_multiplicity as u32,
+            Rational::from(1),
+        )])))
+        .0;
+
+    if !poly.terms().all(|(_, coeff)| coeff.is_integer()) {
+        return None;
+    }
+
+    // Dividing out the content doesn't change the roots, but leaves fewer divisors to try as candidates
+    let poly = &poly.primitive_part();
+
+    // Find all the rational roots using the rational root theorem (https://en.wikipedia.org/wiki/Rational_root_theorem)
+    let ps = integer_factors(poly.get(0).as_integer()?.abs())?;
+    let qs = integer_factors(poly.get(poly.degree()).as_integer()?.abs())?;
+
+    let mut candidates = Vec::new();
+
+    for &p in &ps {
+        for &q in &qs {
+            for potential_root in [Rational::new(-p, q), Rational::new(p, q)] {
+                // Different p/q pairs can reduce to the same candidate, so make sure it's only counted once
+                if !candidates.contains(&potential_root) {
+                    candidates.push(potential_root);
+                }
+            }
+        }
+    }
+
+    for candidate in &candidates {
+        // Check if it's an actual root and if so, how many times it's repeated. This evaluates the derivatives at the
+        // candidate directly, so no derivative polynomials are built no matter how many candidates there are.
+        let multiplicity = poly.multiplicity_at(candidate.clone());
 
-    for i in 1..=n.abs() {
-        if n % i == 0 {
-            if n < 0 {
-                factors.push(-i);
+        roots.extend(iter::repeat_n(candidate.clone(), multiplicity as usize));
+    }
+
+    trace.push(TraceStep::Candidates(candidates));
+
+    Some(roots)
+}
+
+/// Find the real roots of `poly` numerically, sorted in ascending order.
+///
+/// The real roots of the derivative split the real line into intervals on which `poly` is monotonic, so each of them
+/// contains at most one root which can then be found using bisection. Repeated roots are only reported once.
+pub fn real_roots_f64(poly: &Polynomial) -> Vec<f64> {
+    bisect_real_roots(poly, 0.0)
+}
+
+/// How precisely numerical roots should be found, which also determines how they're converted to rationals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// Round to the given number of decimal places, up to the 15 that an `f64` can represent.
+    DecimalPlaces(u32),
+    /// Find the closest rational whose denominator is at most the given value.
+    MaxDenominator(i128),
+}
+
+#[cfg(feature = "std")]
+impl Precision {
+    /// Get the interval width below which bisection can stop, which is well below the spacing between the rationals
+    /// the roots are converted to. Any value within it converts to the same rational, except close to a rounding
+    /// boundary.
+    fn tolerance(self) -> f64 {
+        match self {
+            Precision::DecimalPlaces(places) => 0.1 * 10f64.powi(-(places.min(15) as i32)),
+            // Distinct fractions with denominators of at most `d` are at least 1/d^2 apart, but telling which of two
+            // neighbouring fractions is closer to the root takes more precision than that
+            Precision::MaxDenominator(max_denom) => 0.1 / (max_denom as f64).powi(4),
+        }
+    }
+
+    fn to_rational(self, value: f64) -> Rational {
+        match self {
+            Precision::DecimalPlaces(places) => {
+                let scale = 10i128.pow(places.min(15));
+                Rational::new((value * scale as f64).round() as i128, scale)
+            }
+            Precision::MaxDenominator(max_denom) => Rational::approximate(value, max_denom),
+        }
+    }
+}
+
+/// Find the real roots of `poly` numerically like [`real_roots_f64`], but only as precisely as needed and converted to
+/// rationals according to `precision`.
+///
+/// Requires the `std` feature, as `core` doesn't provide rounding.
+#[cfg(feature = "std")]
+pub fn real_roots_with_precision(poly: &Polynomial, precision: Precision) -> Vec<Rational> {
+    bisect_real_roots(poly, precision.tolerance())
+        .into_iter()
+        .map(|root| precision.to_rational(root))
+        .collect()
+}
+
+/// Find the real roots of `poly` using bisection, stopping once the bracket is at most `tolerance` wide or can't be
+/// split any further.
+fn bisect_real_roots(poly: &Polynomial, tolerance: f64) -> Vec<f64> {
+    let degree = poly.degree();
+    let leading = poly.get(degree).to_f64();
+
+    match degree {
+        0 => return vec![],
+        1 => return vec![-poly.get(0).to_f64() / leading],
+        _ => (),
+    }
+
+    // All roots lie within Cauchy's bound (https://en.wikipedia.org/wiki/Geometrical_properties_of_polynomial_roots#Lagrange's_and_Cauchy's_bounds)
+    let bound = 1.0
+        + (0..degree)
+            .map(|degree| (poly.get(degree).to_f64() / leading).abs())
+            .fold(0.0, f64::max);
+
+    let mut points = vec![-bound];
+    points.append(&mut real_roots_f64(&poly.diff()));
+    points.push(bound);
+
+    // A root that touches the x-axis at a critical point doesn't change sign, so it has to be checked for directly
+    let is_root = |x: f64| {
+        // The sum of the absolute values of all terms, evaluated using Horner's method
+        let magnitude = (0..=degree).rev().fold(0.0, |result, degree| {
+            result * x.abs() + poly.get(degree).to_f64().abs()
+        });
+
+        poly.eval_f64(x).abs() <= magnitude * 1e-12
+    };
+
+    let mut roots = Vec::new();
+
+    for window in points.windows(2) {
+        let (mut low, mut high) = (window[0], window[1]);
+
+        if is_root(low) {
+            if roots.last() != Some(&low) {
+                roots.push(low);
+            }
+
+            continue;
+        }
+
+        if is_root(high) || poly.eval_f64(low).signum() == poly.eval_f64(high).signum() {
+            continue;
+        }
+
+        let low_sign = poly.eval_f64(low).signum();
+
+        loop {
+            let mid = (low + high) / 2.0;
+
+            // Stop once the interval is small enough or can't be split any further
+            if mid == low || mid == high || high - low <= tolerance {
+                break;
+            }
+
+            if poly.eval_f64(mid).signum() == low_sign {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        roots.push(low);
+    }
+
+    roots
+}
+
+/// Find all positive divisors of `n` in ascending order, along with their negations if `n` is negative.
+///
+/// The divisors are built from the prime factors of `n`, which are found by trial division up to 10^6. Returns `None`
+/// if that isn't enough to factor `n` completely, which can only happen if `|n| >= 10^12`.
+///
+/// Every integer divides 0, so `n` must be non-zero. Zero constant terms have to be factored out before using this.
+fn integer_factors(n: i128) -> Option<Vec<i128>> {
+    assert!(n != 0, "integer_factors: 0 has infinitely many factors");
+
+    const MAX_FACTOR: i128 = 1_000_000;
+
+    let mut remaining = n.checked_abs()?;
+    let mut divisors = vec![1];
+    let mut factor = 2;
+
+    while factor <= MAX_FACTOR && factor * factor <= remaining {
+        // Every power of the factor multiplies all the divisors found so far
+        let count = divisors.len();
+        let mut power = 1;
+
+        while remaining % factor == 0 {
+            remaining /= factor;
+            power *= factor;
+
+            for j in 0..count {
+                divisors.push(divisors[j] * power);
+            }
+        }
+
+        factor += 1;
+    }
+
+    // Without any factors up to its square root, what's left over is prime, but that's unknown past the search limit
+    if factor * factor <= remaining {
+        return None;
+    }
+    if remaining > 1 {
+        for j in 0..divisors.len() {
+            divisors.push(divisors[j] * remaining);
+        }
+    }
+
+    divisors.sort_unstable();
+
+    if n < 0 {
+        divisors = divisors.into_iter().flat_map(|i| [-i, i]).collect();
+    }
+
+    Some(divisors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check that `roots` are actually roots of `poly` with the right multiplicities, and if there are as many of them
+    /// as the degree, that multiplying out their linear factors gives back `poly` up to a constant factor.
+    fn assert_roots_are_correct(poly: &Polynomial, roots: &[Root]) {
+        let mut product = Polynomial::from(vec![(0, Rational::from(1))]);
+
+        for root in roots {
+            assert_eq!(
+                poly.eval(root.value.clone()),
+                0,
+                "{} is not a root of {}",
+                root.value,
+                poly
+            );
+            assert_eq!(
+                poly.multiplicity_at(root.value.clone()),
+                root.multiplicity,
+                "wrong multiplicity of the root {} of {}",
+                root.value,
+                poly
+            );
+
+            let factor = Polynomial::from(vec![(0, -&root.value), (1, Rational::from(1))]);
+            product = &product * &factor.pow(root.multiplicity);
+        }
+
+        let (_, remainder) = poly.div_rem(&product);
+        assert_eq!(remainder, Polynomial::zero(), "roots don't divide {}", poly);
+
+        if product.degree() == poly.degree() {
+            assert!(product.equal_up_to_scalar(poly));
+        }
+    }
+
+    /// Build `leading * (x - r1)(x - r2)...` from its roots.
+    fn from_roots(leading: Rational, roots: &[Rational]) -> Polynomial {
+        roots
+            .iter()
+            .fold(Polynomial::from(vec![(0, leading)]), |poly, root| {
+                &poly * &Polynomial::from(vec![(0, -root), (1, Rational::from(1))])
+            })
+    }
+
+    #[test]
+    fn solutions_reconstruct_the_polynomial() {
+        let cases = [
+            (
+                Rational::from(1),
+                vec![Rational::from(-5), Rational::from(-5), Rational::from(5)],
+            ),
+            (
+                Rational::from(3),
+                vec![Rational::new(1, 3), Rational::from(2), Rational::from(-7)],
+            ),
+            (
+                Rational::from(-2),
+                vec![Rational::from(0), Rational::from(0), Rational::new(3, 2)],
+            ),
+            (Rational::from(1), vec![Rational::from(4); 4]),
+            (
+                Rational::from(6),
+                vec![
+                    Rational::new(1, 2),
+                    Rational::new(-2, 3),
+                    Rational::from(3),
+                    Rational::from(3),
+                ],
+            ),
+            (
+                Rational::from(1),
+                vec![
+                    Rational::from(-1),
+                    Rational::from(1),
+                    Rational::from(0),
+                    Rational::from(10),
+                ],
+            ),
+        ];
+
+        for (leading, expected) in cases {
+            let poly = from_roots(leading, &expected);
+            let roots = solve_univariate_polynomial(&poly).unwrap();
+
+            assert_roots_are_correct(&poly, &roots);
+            assert_eq!(
+                roots.iter().map(|root| root.multiplicity).sum::<u32>(),
+                poly.degree(),
+                "missing roots of {}",
+                poly
+            );
+        }
+
+        // Irreducible quadratic factors are left over, so only the rational roots can be checked
+        // (x - 2)(x^2 + 1)(x + 1)
+        let poly = &from_roots(Rational::from(1), &[Rational::from(2), Rational::from(-1)])
+            * &Polynomial::from(vec![(0, Rational::from(1)), (2, Rational::from(1))]);
+
+        assert_roots_are_correct(&poly, &solve_univariate_polynomial(&poly).unwrap());
+    }
+
+    #[test]
+    fn solve_quadratic() {
+        // x^2 - 5x + 6 = (x - 2)(x - 3)
+        assert_eq!(
+            solve_univariate_polynomial(&Polynomial::new(BTreeMap::from([
+                (0, Rational::from(6)),
+                (1, Rational::from(-5)),
+                (2, Rational::from(1)),
+            ]))),
+            Ok(vec![
+                Root {
+                    value: Rational::from(2),
+                    multiplicity: 1
+                },
+                Root {
+                    value: Rational::from(3),
+                    multiplicity: 1
+                },
+            ])
+        );
+
+        // x^2 - 6x + 9 = (x - 3)^2
+        assert_eq!(
+            solve_univariate_polynomial(&Polynomial::new(BTreeMap::from([
+                (0, Rational::from(9)),
+                (1, Rational::from(-6)),
+                (2, Rational::from(1)),
+            ]))),
+            Ok(vec![Root {
+                value: Rational::from(3),
+                multiplicity: 2
+            }])
+        );
+
+        // x^2 - 2
+        assert_eq!(
+            solve_univariate_polynomial(&Polynomial::new(BTreeMap::from([
+                (0, Rational::from(-2)),
+                (2, Rational::from(1)),
+            ]))),
+            Err(SolverError::IrrationalRoots)
+        );
+    }
+
+    #[test]
+    fn solve_cubic() {
+        // x^3 + 5x^2 - 25x - 125 = (x + 5)^2 (x - 5)
+        assert_eq!(
+            solve_univariate_polynomial(&Polynomial::new(BTreeMap::from([
+                (0, Rational::from(-125)),
+                (1, Rational::from(-25)),
+                (2, Rational::from(5)),
+                (3, Rational::from(1)),
+            ]))),
+            Ok(vec![
+                Root {
+                    value: Rational::from(-5),
+                    multiplicity: 2
+                },
+                Root {
+                    value: Rational::from(5),
+                    multiplicity: 1
+                },
+            ])
+        );
+
+        // x^3 - x^2 + x - 1 = (x - 1)(x^2 + 1)
+        assert_eq!(
+            solve_univariate_polynomial(&Polynomial::new(BTreeMap::from([
+                (0, Rational::from(-1)),
+                (1, Rational::from(1)),
+                (2, Rational::from(-1)),
+                (3, Rational::from(1)),
+            ]))),
+            Ok(vec![Root {
+                value: Rational::from(1),
+                multiplicity: 1
+            }])
+        );
+    }
+
+    #[test]
+    fn zero_roots_are_factored_out() {
+        // x^3 - 3x^2 + 2x = x (x - 1)(x - 2)
+        assert_eq!(
+            solve_univariate_polynomial(&Polynomial::new(BTreeMap::from([
+                (1, Rational::from(2)),
+                (2, Rational::from(-3)),
+                (3, Rational::from(1)),
+            ]))),
+            Ok(vec![
+                Root {
+                    value: Rational::from(0),
+                    multiplicity: 1
+                },
+                Root {
+                    value: Rational::from(1),
+                    multiplicity: 1
+                },
+                Root {
+                    value: Rational::from(2),
+                    multiplicity: 1
+                },
+            ])
+        );
+
+        // x^5 - x^3 = x^3 (x + 1)(x - 1)
+        assert_eq!(
+            rational_roots(&Polynomial::new(BTreeMap::from([
+                (3, Rational::from(-1)),
+                (5, Rational::from(1)),
+            ]))),
+            Some(vec![
+                Rational::from(0),
+                Rational::from(0),
+                Rational::from(0),
+                Rational::from(-1),
+                Rational::from(1),
+            ])
+        );
+    }
+
+    #[test]
+    fn multiplicities_without_derivative_polynomials() {
+        // 18 x (x - 1)^3 (x + 2)^2 (3x - 2), whose leading coefficient and constant term give lots of candidates
+        let poly = from_roots(
+            Rational::from(18),
+            &[0, 1, 1, 1, -2, -2].map(Rational::from),
+        ) * Polynomial::from(vec![(0, Rational::from(-2)), (1, Rational::from(3))]);
+
+        crate::polynomial::DIFF_CALLS.with(|calls| calls.set(0));
+
+        let mut roots = rational_roots(&poly).unwrap();
+        let mut trace = Vec::new();
+        rational_roots_with_trace(&poly, &mut trace);
+
+        // Two full searches over more candidates than the degree still don't differentiate anything
+        assert!(
+            matches!(&trace[..], [TraceStep::Candidates(candidates)] if candidates.len() > 2 * poly.degree() as usize)
+        );
+        assert_eq!(crate::polynomial::DIFF_CALLS.with(|calls| calls.get()), 0);
+
+        roots.sort();
+        assert_eq!(
+            roots,
+            [
+                Rational::from(-2),
+                Rational::from(-2),
+                Rational::from(0),
+                Rational::new(2, 3),
+                Rational::from(1),
+                Rational::from(1),
+                Rational::from(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn integer_factors_of_small_numbers() {
+        assert_eq!(integer_factors(1), Some(vec![1]));
+        assert_eq!(integer_factors(-1), Some(vec![-1, 1]));
+        assert_eq!(integer_factors(12), Some(vec![1, 2, 3, 4, 6, 12]));
+        assert_eq!(integer_factors(-6), Some(vec![-1, 1, -2, 2, -3, 3, -6, 6]));
+    }
+
+    #[test]
+    fn integer_factors_of_large_numbers() {
+        // 123456789012 = 2^2 * 3 * 10288065751, the latter of which is prime
+        assert_eq!(
+            integer_factors(123456789012),
+            Some(vec![
+                1,
+                2,
+                3,
+                4,
+                6,
+                12,
+                10288065751,
+                20576131502,
+                30864197253,
+                41152263004,
+                61728394506,
+                123456789012
+            ])
+        );
+        assert_eq!(integer_factors(10000000019), Some(vec![1, 10000000019]));
+        assert_eq!(
+            integer_factors(1 << 100).map(|factors| factors.len()),
+            Some(101)
+        );
+
+        // The product of two primes past the search limit can't be factored
+        assert_eq!(integer_factors(1000003 * 1000033), None);
+        assert_eq!(integer_factors(i128::MAX), None);
+    }
+
+    #[test]
+    fn large_coefficients() {
+        let exact = |roots: Vec<Root<RootValue>>| {
+            roots.into_iter().map(|root| root.value).collect::<Vec<_>>()
+        };
+
+        // x - 123456789013
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-123456789013i128)),
+            (1, Rational::from(1)),
+        ]);
+        assert_eq!(
+            exact(find_roots(&poly)),
+            [RootValue::Exact(Rational::from(123456789013i128))]
+        );
+
+        // 123456789012x^2 - 1 has no rational roots
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-1)),
+            (2, Rational::from(123456789012i128)),
+        ]);
+        assert_eq!(rational_roots(&poly), Some(vec![]));
+        assert_eq!(find_roots(&poly).len(), 2);
+
+        // Too large to factor, but a linear factor is still solved exactly
+        let prime_product = 1000003 * 1000033;
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(prime_product)),
+            (1, Rational::from(2)),
+        ]);
+        assert_eq!(rational_roots(&poly), None);
+        assert_eq!(
+            exact(find_roots(&poly)),
+            [RootValue::Exact(Rational::new(-prime_product, 2))]
+        );
+
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(prime_product)),
+            (3, Rational::from(1)),
+        ]);
+        assert_eq!(
+            solve_univariate_polynomial(&poly),
+            Err(SolverError::CoefficientsTooLarge)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "integer_factors: 0 has infinitely many factors")]
+    fn integer_factors_of_zero() {
+        integer_factors(0);
+    }
+
+    #[test]
+    fn inequalities() {
+        let exact = |value: i32| Some(RootValue::Exact(Rational::from(value)));
+
+        // x^2 - 4 > 0
+        let poly = Polynomial::from(vec![(0, Rational::from(-4)), (2, Rational::from(1))]);
+        let intervals = solve_inequality(&poly, Ordering::Greater);
+
+        assert_eq!(
+            intervals,
+            vec![
+                Interval {
+                    lower: None,
+                    upper: exact(-2)
+                },
+                Interval {
+                    lower: exact(2),
+                    upper: None
+                },
+            ]
+        );
+        assert_eq!(
+            intervals
+                .iter()
+                .map(Interval::to_string)
+                .collect::<Vec<_>>()
+                .join(" ∪ "),
+            "(-inf, -2) ∪ (2, inf)"
+        );
+
+        assert_eq!(
+            solve_inequality(&poly, Ordering::Less),
+            vec![Interval {
+                lower: exact(-2),
+                upper: exact(2)
+            }]
+        );
+
+        // (x - 1)^2 < 0 never holds, while x^2 + 1 > 0 always does
+        let square = Polynomial::from(vec![
+            (0, Rational::from(1)),
+            (1, Rational::from(-2)),
+            (2, Rational::from(1)),
+        ]);
+        assert_eq!(solve_inequality(&square, Ordering::Less), vec![]);
+
+        let positive = Polynomial::from(vec![(0, Rational::from(1)), (2, Rational::from(1))]);
+        assert_eq!(
+            solve_inequality(&positive, Ordering::Greater),
+            vec![Interval {
+                lower: None,
+                upper: None
+            }]
+        );
+
+        // The sign doesn't change at the double root of (x - 1)^2 (x + 2) > 0
+        let poly =
+            &square * &Polynomial::from(vec![(0, Rational::from(2)), (1, Rational::from(1))]);
+        assert_eq!(
+            solve_inequality(&poly, Ordering::Greater),
+            vec![
+                Interval {
+                    lower: exact(-2),
+                    upper: exact(1)
+                },
+                Interval {
+                    lower: exact(1),
+                    upper: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn inequalities_with_irrational_bounds() {
+        let surd = |coeff| {
+            Some(RootValue::Surd {
+                rational: Rational::from(0),
+                coeff: Rational::from(coeff),
+                radicand: 2,
+            })
+        };
+
+        // x^2 - 2 > 0
+        let poly = Polynomial::from(vec![(0, Rational::from(-2)), (2, Rational::from(1))]);
+
+        assert_eq!(
+            solve_inequality(&poly, Ordering::Greater),
+            vec![
+                Interval {
+                    lower: None,
+                    upper: surd(-1)
+                },
+                Interval {
+                    lower: surd(1),
+                    upper: None
+                },
+            ]
+        );
+
+        // x^3 - 2x - 3 has a single real root at about 1.893, which can only be approximated
+        let poly = Polynomial::from(vec![
+            (0, Rational::from(-3)),
+            (1, Rational::from(-2)),
+            (3, Rational::from(1)),
+        ]);
+        let intervals = solve_inequality(&poly, Ordering::Less);
+
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(intervals[0].lower, None);
+        assert_eq!(
+            format!("{:.3}", intervals[0].upper.clone().unwrap()),
+            "≈ 1.893"
+        );
+
+        // Surds and rationals are ordered by value, e.g. in (x^2 - 2)(x - 1) < 0
+        let poly = &Polynomial::from(vec![(0, Rational::from(-2)), (2, Rational::from(1))])
+            * &Polynomial::from(vec![(0, Rational::from(-1)), (1, Rational::from(1))]);
+
+        assert_eq!(
+            solve_inequality(&poly, Ordering::Less)
+                .iter()
+                .map(Interval::to_string)
+                .collect::<Vec<_>>(),
+            ["(-inf, -√2)", "(1, √2)"]
+        );
+    }
+
+    #[test]
+    fn unsupported_degree_is_an_error() {
+        // x^7 + x + 1 has no rational roots
+        assert_eq!(
+            solve_univariate_polynomial(&Polynomial::new(BTreeMap::from([
+                (0, Rational::from(1)),
+                (1, Rational::from(1)),
+                (7, Rational::from(1)),
+            ]))),
+            Err(SolverError::UnsupportedDegree(7))
+        );
+    }
+
+    #[test]
+    fn only_rational_roots() {
+        // (x - 2)(x^2 - 2) = x^3 - 2x^2 - 2x + 4
+        assert_eq!(
+            solve_rational_roots_only(&Polynomial::from(vec![
+                (0, Rational::from(4)),
+                (1, Rational::from(-2)),
+                (2, Rational::from(-2)),
+                (3, Rational::from(1)),
+            ])),
+            vec![Root {
+                value: Rational::from(2),
+                multiplicity: 1
+            }]
+        );
+
+        // (x - 1/2)^2 (x^7 + x + 1) has a degree the solver can't handle otherwise
+        let poly = Polynomial::from(vec![(0, Rational::new(-1, 2)), (1, Rational::from(1))]).pow(2)
+            * Polynomial::from(vec![
+                (0, Rational::from(1)),
+                (1, Rational::from(1)),
+                (7, Rational::from(1)),
+            ]);
+        assert_eq!(
+            solve_rational_roots_only(&poly),
+            vec![Root {
+                value: Rational::new(1, 2),
+                multiplicity: 2
+            }]
+        );
+
+        assert!(
+            solve_rational_roots_only(&Polynomial::from(vec![(0, Rational::from(3))])).is_empty()
+        );
+    }
+
+    #[test]
+    fn trace() {
+        // x^3 - 2x^2 - x + 2 = (x + 1)(x - 1)(x - 2)
+        let mut trace = Vec::new();
+        let roots = find_roots_with_trace(
+            &Polynomial::from(vec![
+                (0, Rational::from(2)),
+                (1, Rational::from(-1)),
+                (2, Rational::from(-2)),
+                (3, Rational::from(1)),
+            ]),
+            &mut trace,
+        );
+
+        assert_eq!(roots.len(), 3);
+        assert_eq!(
+            trace[0],
+            TraceStep::Candidates([-1, 1, -2, 2].map(Rational::from).to_vec())
+        );
+        assert_eq!(
+            trace[1],
+            TraceStep::Root {
+                value: Rational::from(-1),
+                multiplicity: 1
+            }
+        );
+        assert_eq!(trace[2].to_string(), "divide it out, leaving x^2 - 3x + 2");
+        assert_eq!(trace.len(), 7);
+        assert_eq!(trace[6].to_string(), "divide it out, leaving 1");
+
+        // (x - 1)^2 (x^2 - 2) has irrational roots left over
+        let mut trace = Vec::new();
+        find_roots_with_trace(
+            &(Polynomial::from(vec![(0, Rational::from(-1)), (1, Rational::from(1))]).pow(2)
+                * Polynomial::from(vec![(0, Rational::from(-2)), (2, Rational::from(1))])),
+            &mut trace,
+        );
+
+        assert_eq!(
+            trace
+                .iter()
+                .map(|step| step.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "try the rational candidates {-1, 1, -2, 2}",
+                "found the root 1 (×2)",
+                "divide it out, leaving x^2 - 2",
+                "solve the rest for {-√2, √2}",
+            ]
+        );
+    }
+
+    #[test]
+    fn surds_share_a_common_denominator() {
+        // The quadratic formula gives (4 ± √48)/8 = (4 ± 4√3)/8 for 4x^2 - 4x - 2, which simplifies to (1 ± √3)/2
+        let roots = find_roots(&Polynomial::from(vec![
+            (0, Rational::from(-2)),
+            (1, Rational::from(-4)),
+            (2, Rational::from(4)),
+        ]));
+
+        assert_eq!(roots[0].value.as_fraction(), Some((1, -1, 2)));
+        assert_eq!(roots[1].value.as_fraction(), Some((1, 1, 2)));
+        assert_eq!(roots[1].value.to_string(), "(1 + √3)/2");
+
+        // 6x^2 - 6x + 1 has roots 1/2 ± √3/6 = (3 ± √3)/6
+        let roots = find_roots(&Polynomial::from(vec![
+            (0, Rational::from(1)),
+            (1, Rational::from(-6)),
+            (2, Rational::from(6)),
+        ]));
+
+        assert_eq!(roots[0].value.as_fraction(), Some((3, -1, 6)));
+        assert_eq!(roots[0].value.to_string(), "(3 - √3)/6");
+        assert_eq!(roots[0].value.to_latex(), "\\frac{3 - \\sqrt{3}}{6}");
+
+        // Parts that don't need a common denominator are left alone
+        assert_eq!(
+            RootValue::Surd {
+                rational: Rational::from(0),
+                coeff: Rational::new(3, 2),
+                radicand: 5
             }
-            factors.push(i);
+            .to_string(),
+            "3√5/2"
+        );
+        assert_eq!(RootValue::Exact(Rational::new(1, 2)).as_fraction(), None);
+    }
+
+    #[test]
+    fn tagged_roots() {
+        // (x - 1)(x^2 - 2) = x^3 - x^2 - 2x + 2
+        let roots = find_roots(&Polynomial::from(vec![
+            (0, Rational::from(2)),
+            (1, Rational::from(-2)),
+            (2, Rational::from(-1)),
+            (3, Rational::from(1)),
+        ]));
+
+        let surd = |coeff| RootValue::Surd {
+            rational: Rational::from(0),
+            coeff: Rational::from(coeff),
+            radicand: 2,
+        };
+
+        assert_eq!(
+            roots
+                .iter()
+                .map(|root| root.value.clone())
+                .collect::<Vec<_>>(),
+            vec![RootValue::Exact(Rational::from(1)), surd(-1), surd(1)]
+        );
+        assert!(roots
+            .iter()
+            .all(|root| root.multiplicity == 1 && root.value.is_exact()));
+        assert_eq!(roots[1].value.to_string(), "-√2");
+        assert_eq!(roots[1].value.to_latex(), "-\\sqrt{2}");
+
+        // (x - 2)^2 (x^2 + x + 1) has complex roots -1/2 ± i√3/2
+        let roots = find_roots(
+            &(Polynomial::from(vec![(0, Rational::from(-2)), (1, Rational::from(1))]).pow(2)
+                * Polynomial::from(vec![
+                    (0, Rational::from(1)),
+                    (1, Rational::from(1)),
+                    (2, Rational::from(1)),
+                ])),
+        );
+
+        assert_eq!(
+            roots[0],
+            Root {
+                value: RootValue::Exact(Rational::from(2)),
+                multiplicity: 2
+            }
+        );
+        assert_eq!(roots[1].value.to_string(), "(-1 - i√3)/2");
+        assert_eq!(roots[2].value.to_string(), "(-1 + i√3)/2");
+        assert_eq!(roots[2].value.to_latex(), "\\frac{-1 + i\\sqrt{3}}{2}");
+
+        // x^2/2 - x - 4 = (x^2 - 2x - 8)/2 has rational roots, and 2x^2 - 2x - 1 has surd roots (1 ± √3)/2
+        let roots = find_roots(&Polynomial::from(vec![
+            (0, Rational::from(-4)),
+            (1, Rational::from(-1)),
+            (2, Rational::new(1, 2)),
+        ]));
+        assert!(roots
+            .iter()
+            .all(|root| matches!(root.value, RootValue::Exact(_))));
+
+        let roots = find_roots(&Polynomial::from(vec![
+            (0, Rational::from(-1)),
+            (1, Rational::from(-2)),
+            (2, Rational::from(2)),
+        ]));
+        assert_eq!(roots[0].value.to_string(), "(1 - √3)/2");
+        assert_eq!(roots[1].value.to_string(), "(1 + √3)/2");
+        assert_eq!(roots[1].value.to_latex(), "\\frac{1 + \\sqrt{3}}{2}");
+
+        // x^2 + 4 has purely imaginary roots
+        let roots = find_roots(&Polynomial::from(vec![
+            (0, Rational::from(4)),
+            (2, Rational::from(1)),
+        ]));
+        assert_eq!(roots[1].value.to_string(), "2i");
+        assert_eq!(roots[1].value.to_latex(), "2i");
+
+        // x^3 - 3x + 1 has three irrational roots which can only be approximated
+        let roots = find_roots(&Polynomial::from(vec![
+            (0, Rational::from(1)),
+            (1, Rational::from(-3)),
+            (3, Rational::from(1)),
+        ]));
+
+        assert_eq!(roots.len(), 3);
+        assert!(roots.iter().all(|root| !root.value.is_exact()));
+        assert_eq!(format!("{:.3}", roots[2].value), "≈ 1.532");
+    }
+
+    #[test]
+    fn repeated_and_factored_irrational_roots() {
+        let x2_minus =
+            |n: i32| Polynomial::from(vec![(0, Rational::from(-n)), (2, Rational::from(1))]);
+        let values = |roots: &[Root<RootValue>]| {
+            roots
+                .iter()
+                .map(|root| (root.value.to_string(), root.multiplicity))
+                .collect::<Vec<_>>()
+        };
+        let expected = |values: &[(&str, u32)]| {
+            values
+                .iter()
+                .map(|&(value, multiplicity)| (value.to_string(), multiplicity))
+                .collect::<Vec<_>>()
+        };
+
+        // (x^2 - 2)^2 has the double roots ±√2
+        assert_eq!(
+            values(&find_roots(&x2_minus(2).pow(2))),
+            expected(&[("-√2", 2), ("√2", 2)])
+        );
+
+        // (x^2 - 2)(x^2 - 3) is square-free, but splits into two quadratics over the rationals
+        assert_eq!(
+            values(&find_roots(&(x2_minus(2) * x2_minus(3)))),
+            expected(&[("-√2", 1), ("√2", 1), ("-√3", 1), ("√3", 1)])
+        );
+
+        // (x - 1)(x^2 - 3)^3 (2x^2 - 2x - 1)(x^2 + 1), where the leading coefficient of one factor isn't 1
+        let poly = Polynomial::from(vec![(0, Rational::from(-1)), (1, Rational::from(1))])
+            * x2_minus(3).pow(3)
+            * Polynomial::from(vec![
+                (0, Rational::from(-1)),
+                (1, Rational::from(-2)),
+                (2, Rational::from(2)),
+            ])
+            * x2_minus(-1);
+        assert_eq!(
+            values(&find_roots(&poly)),
+            expected(&[
+                ("1", 1),
+                ("(1 - √3)/2", 1),
+                ("(1 + √3)/2", 1),
+                ("-i", 1),
+                ("i", 1),
+                ("-√3", 3),
+                ("√3", 3),
+            ])
+        );
+
+        // Factors without quadratic factors of their own are still approximated, and keep their multiplicity
+        let roots = find_roots(
+            &Polynomial::from(vec![
+                (0, Rational::from(1)),
+                (1, Rational::from(-3)),
+                (3, Rational::from(1)),
+            ])
+            .pow(2),
+        );
+        assert_eq!(roots.len(), 3);
+        assert!(roots
+            .iter()
+            .all(|root| !root.value.is_exact() && root.multiplicity == 2));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn numerical_roots_with_precision() {
+        let x_squared_minus_2 =
+            Polynomial::from(vec![(0, Rational::from(-2)), (2, Rational::from(1))]);
+
+        let roots = real_roots_with_precision(&x_squared_minus_2, Precision::DecimalPlaces(12));
+        assert_eq!(roots.len(), 2);
+
+        for root in roots {
+            assert!((root.to_f64().abs() - core::f64::consts::SQRT_2).abs() < 1e-12);
+            assert!(x_squared_minus_2.eval(root).to_f64().abs() < 1e-11);
         }
+
+        assert_eq!(
+            real_roots_with_precision(&x_squared_minus_2, Precision::DecimalPlaces(3)),
+            [Rational::new(-1414, 1000), Rational::new(1414, 1000)]
+        );
+
+        // 7/5 is the best approximation of √2 with a denominator of at most 10
+        assert_eq!(
+            real_roots_with_precision(&x_squared_minus_2, Precision::MaxDenominator(10)),
+            [Rational::new(-7, 5), Rational::new(7, 5)]
+        );
+
+        // x^3 - 2 has the single real root ∛2 ≈ 1.259921
+        let root = real_roots_with_precision(
+            &Polynomial::from(vec![(0, Rational::from(-2)), (3, Rational::from(1))]),
+            Precision::MaxDenominator(1000),
+        )[0]
+        .clone();
+        assert!(root.denom() <= BigInt::from(1000));
+        assert!((root.pow(3) - Rational::from(2)).to_f64().abs() < 1e-5);
     }
 
-    factors
+    #[test]
+    fn square_factors() {
+        assert_eq!(split_square_factors(72), (6, 2));
+        assert_eq!(split_square_factors(7), (1, 7));
+        assert_eq!(split_square_factors(49), (7, 1));
+        // 1000003 is a prime too large for trial division, so its square is only found if nothing else is left
+        assert_eq!(split_square_factors(1_000_003 * 1_000_003), (1_000_003, 1));
+        assert_eq!(
+            split_square_factors(2 * 1_000_003 * 1_000_003),
+            (1, 2 * 1_000_003 * 1_000_003)
+        );
+    }
 }
@@ -0,0 +1,43 @@
+use std::process::Command;
+
+#[test]
+fn solves_hardcoded_examples() {
+    let output = Command::new(env!("CARGO_BIN_EXE_sym"))
+        .output()
+        .expect("failed to run the sym binary");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("x^3 + 5x^2 - 25x - 125 = 0\n=> x = {-5 (×2), 5}"));
+    assert!(stdout.contains("-27 + 27 x - 9 x^2 + x^3 = 0\n=> x = {3 (×3)}"));
+    assert!(stdout.contains("x^4 - 16 x^3 + 96 x^2 - 256 x + 256\n=> x = {4 (×4)}"));
+}
+
+#[test]
+fn annotates_only_repeated_roots() {
+    let output = Command::new(env!("CARGO_BIN_EXE_sym"))
+        .output()
+        .expect("failed to run the sym binary");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Simple roots are printed bare, and each root is only printed once
+    assert!(!stdout.contains("(×1)"));
+    assert!(!stdout.contains("-5, -5"));
+    assert!(stdout.contains("-5 (×2), 5}"));
+}
+
+#[test]
+fn verbose_flag_prints_the_trace() {
+    let output = Command::new(env!("CARGO_BIN_EXE_sym"))
+        .arg("--verbose")
+        .output()
+        .expect("failed to run the sym binary");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("try the rational candidates {-1, 1, -3, 3, -9, 9, -27, 27}"));
+    assert!(stdout.contains("found the root 3 (×3)\n"));
+}
@@ -0,0 +1,14 @@
+use std::process::Command;
+
+#[test]
+fn builds_without_std() {
+    // Use a separate target directory, as the one used by this test run is locked
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--no-default-features", "--target-dir"])
+        .arg(concat!(env!("CARGO_TARGET_TMPDIR"), "/no_std"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("failed to run cargo");
+
+    assert!(status.success());
+}